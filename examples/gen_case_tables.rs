@@ -0,0 +1,101 @@
+//! Generates `src/case/tables.rs` from the standard library's full case mappings, keeping only
+//! those that are a single char, and `char::is_alphanumeric`.
+//!
+//! Run with `cargo run --example gen_case_tables > tables.rs`, then move `tables.rs` over
+//! `src/case/tables.rs`. Redirecting straight to `src/case/tables.rs` would empty it before the
+//! crate is built. The tables follow the Unicode version of the toolchain that runs this, which
+//! is recorded in the output.
+
+use std::fmt::Write;
+
+/// Returns the single char that `mapped` yields, or `None` if it yields more than one.
+fn single(mut mapped: impl Iterator<Item = char>) -> Option<char> {
+    let c = mapped.next()?;
+    mapped.next().map_or(Some(c), |_| None)
+}
+
+/// Groups the chars that `map` changes into runs of `(first, last, delta, stride)`.
+fn runs(map: fn(char) -> Option<char>) -> Vec<(u32, u32, i32, u32)> {
+    let mut runs: Vec<(u32, u32, i32, u32)> = Vec::new();
+    for c in (0..=0x10ffff).filter_map(char::from_u32) {
+        let mapped = match map(c) {
+            Some(mapped) if mapped != c => mapped,
+            _ => continue,
+        };
+        let (c, delta) = (c as u32, mapped as i32 - c as i32);
+
+        if let Some(run) = runs.last_mut() {
+            let (first, last, run_delta, stride) = *run;
+            if run_delta == delta {
+                if first == last && c - last <= 2 {
+                    *run = (first, c, delta, c - last);
+                    continue;
+                } else if first != last && c - last == stride {
+                    run.1 = c;
+                    continue;
+                }
+            }
+        }
+        runs.push((c, c, delta, 1));
+    }
+
+    runs
+}
+
+/// Groups the chars for which `is_alphanumeric` is `true` into ranges of `(first, last)`.
+fn alphanumeric_ranges() -> Vec<(u32, u32)> {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for c in (0..=0x10ffff).filter_map(char::from_u32).filter(|c| c.is_alphanumeric()) {
+        let c = c as u32;
+        match ranges.last_mut() {
+            Some(range) if range.1 + 1 == c => range.1 = c,
+            _ => ranges.push((c, c)),
+        }
+    }
+
+    ranges
+}
+
+fn table(out: &mut String, name: &str, runs: &[(u32, u32, i32, u32)]) {
+    writeln!(out, "pub(super) const {}: &[(u32, u32, i32, u32)] = &[", name).unwrap();
+    for &(first, last, delta, stride) in runs {
+        writeln!(out, "    (0x{:04x}, 0x{:04x}, {}, {}),", first, last, delta, stride).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn main() {
+    let (major, minor, update) = char::UNICODE_VERSION;
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Single-char case mappings, as runs of `(first, last, delta, stride)`: every \
+         `stride`th\n\
+         // char from `first` to `last` maps to itself plus `delta`.\n\
+         //\n\
+         // Generated by `examples/gen_case_tables.rs` from `char::to_uppercase` and \
+         `char::to_lowercase`\n\
+         // (Unicode {}.{}.{}), keeping only the chars that map to a single other char.\n",
+        major, minor, update
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "pub(super) const UNICODE_VERSION: (u8, u8, u8) = ({}, {}, {});\n",
+        major, minor, update
+    )
+    .unwrap();
+    table(&mut out, "UPPERCASE", &runs(|c| single(c.to_uppercase())));
+    writeln!(out).unwrap();
+    table(&mut out, "LOWERCASE", &runs(|c| single(c.to_lowercase())));
+
+    writeln!(out).unwrap();
+    writeln!(out, "// Ranges of the chars for which `char::is_alphanumeric` is `true`.\n").unwrap();
+    writeln!(out, "pub(super) const ALPHANUMERIC: &[(u32, u32)] = &[").unwrap();
+    for (first, last) in alphanumeric_ranges() {
+        writeln!(out, "    (0x{:04x}, 0x{:04x}),", first, last).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+
+    print!("{}", out);
+}
@@ -0,0 +1,239 @@
+//! Base64 encoding and decoding in constants.
+
+/// Which alphabet to use and whether to pad with `=`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    alphabet: &'static [u8; 64],
+    pad: bool,
+}
+
+/// The standard alphabet from RFC 4648, with padding.
+pub const STANDARD: Config = Config { alphabet: STANDARD_ALPHABET, pad: true };
+/// The standard alphabet from RFC 4648, without padding.
+pub const STANDARD_NO_PAD: Config = Config { alphabet: STANDARD_ALPHABET, pad: false };
+/// The URL and filename safe alphabet from RFC 4648, with padding.
+pub const URL_SAFE: Config = Config { alphabet: URL_SAFE_ALPHABET, pad: true };
+/// The URL and filename safe alphabet from RFC 4648, without padding.
+pub const URL_SAFE_NO_PAD: Config = Config { alphabet: URL_SAFE_ALPHABET, pad: false };
+
+const STANDARD_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Returns the length of `len` bytes encoded with `config`.
+pub const fn encoded_len(len: usize, config: Config) -> usize {
+    if config.pad {
+        len.div_ceil(3) * 4
+    } else {
+        len / 3 * 4 + [0, 2, 3][len % 3]
+    }
+}
+
+/// Encodes `bytes` with `config` into a `[u8; N]`.
+///
+/// Panics if `N` isn't [`encoded_len`] of `bytes.len()`.
+pub const fn encode<const N: usize>(bytes: &[u8], config: Config) -> [u8; N] {
+    if encoded_len(bytes.len(), config) != N {
+        panic!("const_base64_encode: output length `N` is not the encoded length");
+    }
+
+    let alphabet = config.alphabet;
+    let mut out = [b'='; N];
+    let mut i = 0;
+    let mut o = 0;
+    while i < bytes.len() {
+        let rest = bytes.len() - i;
+        let b0 = bytes[i] as u32;
+        let b1 = if rest > 1 { bytes[i + 1] as u32 } else { 0 };
+        let b2 = if rest > 2 { bytes[i + 2] as u32 } else { 0 };
+        let group = (b0 << 16) | (b1 << 8) | b2;
+
+        out[o] = alphabet[(group >> 18) as usize & 0x3f];
+        out[o + 1] = alphabet[(group >> 12) as usize & 0x3f];
+        if rest > 1 {
+            out[o + 2] = alphabet[(group >> 6) as usize & 0x3f];
+        }
+        if rest > 2 {
+            out[o + 3] = alphabet[group as usize & 0x3f];
+        }
+
+        i += 3;
+        o += 4;
+    }
+
+    out
+}
+
+/// Returns the number of bytes that `s` decodes to with `config`.
+///
+/// Panics if `s` isn't a valid length for `config`.
+pub const fn decoded_len(s: &str, config: Config) -> usize {
+    let s = s.as_bytes();
+    let mut len = s.len();
+    if config.pad {
+        if len % 4 != 0 {
+            panic!("const_base64_decode: length is not a multiple of 4");
+        }
+        if len > 0 && s[len - 1] == b'=' {
+            len -= 1;
+            if s[len - 1] == b'=' {
+                len -= 1;
+            }
+        }
+    }
+    if len % 4 == 1 {
+        panic!("const_base64_decode: invalid length");
+    }
+
+    len / 4 * 3 + [0, 0, 1, 2][len % 4]
+}
+
+/// Decodes `s` with `config` into a `[u8; N]`.
+///
+/// Panics if `s` isn't valid for `config`, or if `N` isn't [`decoded_len`] of `s`.
+pub const fn decode<const N: usize>(s: &str, config: Config) -> [u8; N] {
+    if decoded_len(s, config) != N {
+        panic!("const_base64_decode: output length `N` is not the decoded length");
+    }
+
+    let s = s.as_bytes();
+    let digits = N / 3 * 4 + [0, 2, 3][N % 3];
+    let mut out = [0u8; N];
+    let mut i = 0;
+    let mut o = 0;
+    while i < digits {
+        let rest = digits - i;
+        let mut group = 0u32;
+        let mut j = 0;
+        while j < 4 {
+            let value = if j < rest { value(config.alphabet, s[i + j]) } else { 0 };
+            group = (group << 6) | value as u32;
+            j += 1;
+        }
+
+        out[o] = (group >> 16) as u8;
+        if rest > 2 {
+            out[o + 1] = (group >> 8) as u8;
+        } else if (group >> 8) & 0xff != 0 {
+            panic!("const_base64_decode: non-zero trailing bits");
+        }
+        if rest > 3 {
+            out[o + 2] = group as u8;
+        } else if group & 0xff != 0 {
+            panic!("const_base64_decode: non-zero trailing bits");
+        }
+
+        i += 4;
+        o += 3;
+    }
+
+    out
+}
+
+const fn value(alphabet: &[u8; 64], b: u8) -> u8 {
+    let mut i = 0;
+    while i < 64 {
+        if alphabet[i] == b {
+            return i as u8;
+        }
+        i += 1;
+    }
+
+    panic!("const_base64_decode: invalid base64 digit")
+}
+
+/// Base64-encodes a constant into a `&'static str`.
+///
+/// Takes the same arguments as [`const_concat_bytes!`], so strings are encoded as UTF-8, and
+/// optionally the name of a config from [`base64`](crate::base64), which defaults to `STANDARD`:
+/// `const_base64_encode!(TOKEN, URL_SAFE_NO_PAD)`.
+#[macro_export]
+macro_rules! const_base64_encode {
+    ($bytes:expr) => {
+        $crate::const_base64_encode!($bytes, STANDARD)
+    };
+    ($bytes:expr, $config:ident) => {{
+        const __BYTES: &[u8] = $crate::bytes::ByteArg($bytes).as_bytes();
+        const __LEN: usize = $crate::base64::encoded_len(__BYTES.len(), $crate::base64::$config);
+        const __OUT: [u8; __LEN] =
+            $crate::base64::encode::<__LEN>(__BYTES, $crate::base64::$config);
+
+        unsafe { $crate::from_utf8_unchecked(&__OUT) }
+    }};
+}
+
+/// Decodes a constant base64 string into a `[u8; N]`, failing to compile if it isn't valid.
+///
+/// Takes the name of a config like [`const_base64_encode!`].
+#[macro_export]
+macro_rules! const_base64_decode {
+    ($s:expr) => {
+        $crate::const_base64_decode!($s, STANDARD)
+    };
+    ($s:expr, $config:ident) => {{
+        const __S: &str = $s;
+        const __LEN: usize = $crate::base64::decoded_len(__S, $crate::base64::$config);
+        const __OUT: [u8; __LEN] = $crate::base64::decode::<__LEN>(__S, $crate::base64::$config);
+        __OUT
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, STANDARD};
+    use crate::const_concat;
+
+    #[test]
+    fn rfc_4648_vectors() {
+        assert_eq!(const_base64_encode!(""), "");
+        assert_eq!(const_base64_encode!("f"), "Zg==");
+        assert_eq!(const_base64_encode!("fo"), "Zm8=");
+        assert_eq!(const_base64_encode!("foo"), "Zm9v");
+        assert_eq!(const_base64_encode!("foob"), "Zm9vYg==");
+        assert_eq!(const_base64_encode!("fooba"), "Zm9vYmE=");
+        assert_eq!(const_base64_encode!("foobar"), "Zm9vYmFy");
+        assert_eq!(const_base64_encode!("fooba", STANDARD_NO_PAD), "Zm9vYmE");
+        assert_eq!(&const_base64_decode!("Zm9vYg=="), b"foob");
+        assert_eq!(&const_base64_decode!("Zm9vYmE", STANDARD_NO_PAD), b"fooba");
+    }
+
+    #[test]
+    fn url_safe() {
+        const BYTES: [u8; 5] = [0xfb, 0xff, 0xbf, 0x00, 0x3e];
+
+        assert_eq!(const_base64_encode!(BYTES), "+/+/AD4=");
+        assert_eq!(const_base64_encode!(BYTES, URL_SAFE), "-_-_AD4=");
+        assert_eq!(const_base64_encode!(BYTES, URL_SAFE_NO_PAD), "-_-_AD4");
+        assert_eq!(const_base64_decode!("-_-_AD4=", URL_SAFE), BYTES);
+        assert_eq!(const_base64_decode!("-_-_AD4", URL_SAFE_NO_PAD), BYTES);
+    }
+
+    #[test]
+    fn basic_auth_header() {
+        const USER: &str = "aladdin";
+        const PASSWORD: &str = "opensesame";
+        const HEADER: &str =
+            const_concat!("Basic ", const_base64_encode!(const_concat!(USER, ":", PASSWORD)));
+
+        assert_eq!(HEADER, "Basic YWxhZGRpbjpvcGVuc2VzYW1l");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid base64 digit")]
+    fn decode_invalid_digit() {
+        decode::<3>("Zm9-", STANDARD);
+    }
+
+    #[test]
+    #[should_panic(expected = "length is not a multiple of 4")]
+    fn decode_missing_padding() {
+        decode::<2>("Zm8", STANDARD);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero trailing bits")]
+    fn decode_trailing_bits() {
+        decode::<1>("Zh==", STANDARD);
+    }
+}
@@ -0,0 +1,149 @@
+/// Wrapper used by [`const_concat_bytes!`] to turn each of its arguments into a byte slice.
+///
+/// Each supported argument type gets its own inherent `as_bytes`, since trait methods can't be
+/// called in constants.
+pub struct ByteArg<T>(pub T);
+
+impl ByteArg<u8> {
+    pub const fn as_bytes(&self) -> &[u8] {
+        std::slice::from_ref(&self.0)
+    }
+}
+
+impl<'a> ByteArg<&'a [u8]> {
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'a, const N: usize> ByteArg<&'a [u8; N]> {
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<const N: usize> ByteArg<[u8; N]> {
+    pub const fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<'a> ByteArg<&'a str> {
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl<'a> ByteArg<&'a std::ffi::CStr> {
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.0.to_bytes()
+    }
+}
+
+/// Converts `bytes`, which ends in its only NUL byte, to a `&CStr`.
+///
+/// Panics if there's a NUL byte before the end.
+pub const fn to_cstr(bytes: &[u8]) -> &std::ffi::CStr {
+    match std::ffi::CStr::from_bytes_with_nul(bytes) {
+        Ok(cstr) => cstr,
+        Err(_) => panic!("const_concat_cstr: argument contains a NUL byte"),
+    }
+}
+
+/// Concatenates byte strings, byte slices, byte arrays and single `u8`s into a
+/// `&'static [u8; N]`, which coerces to `&'static [u8]`.
+#[macro_export]
+macro_rules! const_concat_bytes {
+    () => {{
+        const __BYTES: [u8; 0] = [];
+        &__BYTES
+    }};
+    ($a:expr) => {{
+        const __A: &[u8] = $crate::bytes::ByteArg($a).as_bytes();
+        const __BYTES: [u8; __A.len()] =
+            $crate::checked_concat::<{ __A.len() }, 0, { __A.len() }>(__A, &[]);
+
+        &__BYTES
+    }};
+    ($a:expr, $b:expr) => {{
+        const __A: &[u8] = $crate::bytes::ByteArg($a).as_bytes();
+        const __B: &[u8] = $crate::bytes::ByteArg($b).as_bytes();
+        const __BYTES: [u8; __A.len() + __B.len()] =
+            $crate::checked_concat::<{ __A.len() }, { __B.len() }, { __A.len() + __B.len() }>(
+                __A, __B,
+            );
+
+        &__BYTES
+    }};
+    ($a:expr, $($rest:expr),*) => {{
+        const __TAIL: &[u8] = $crate::const_concat_bytes!($($rest),*);
+        $crate::const_concat_bytes!($a, __TAIL)
+    }};
+    ($a:expr, $($rest:expr),*,) => {
+        $crate::const_concat_bytes!($a, $($rest),*)
+    };
+}
+
+/// Concatenates constants into a NUL-terminated `&'static CStr`.
+///
+/// Takes the same arguments as [`const_concat_bytes!`], as well as `&CStr`s, and fails to compile
+/// if any of them contains a NUL byte.
+#[macro_export]
+macro_rules! const_concat_cstr {
+    () => {
+        $crate::const_concat_cstr!(@to_cstr $crate::const_concat_bytes!(0u8))
+    };
+    ($($arg:expr),+) => {
+        $crate::const_concat_cstr!(@to_cstr $crate::const_concat_bytes!($($arg),+, 0u8))
+    };
+    ($($arg:expr),+,) => {
+        $crate::const_concat_cstr!($($arg),+)
+    };
+    (@to_cstr $bytes:expr) => {{
+        const __CSTR: &::std::ffi::CStr = $crate::bytes::to_cstr($bytes);
+        __CSTR
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_cstr;
+    use std::ffi::CStr;
+
+    #[test]
+    fn mixed_byte_arguments() {
+        const MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+        const VERSION: u8 = 1;
+        const PAYLOAD: &[u8] = b"payload";
+        const HEADER: &[u8] = const_concat_bytes!(MAGIC, VERSION, b"\x00\x00", PAYLOAD, 0xffu8,);
+        const SIZED: &[u8; 7] = const_concat_bytes!(b"ab", b"load", b'!');
+
+        assert_eq!(HEADER, b"\x7fELF\x01\x00\x00payload\xff");
+        assert_eq!(SIZED, b"abload!");
+    }
+
+    #[test]
+    fn empty_and_single() {
+        const EMPTY: &[u8] = const_concat_bytes!();
+        const SINGLE: &[u8; 3] = const_concat_bytes!(b"abc");
+
+        assert_eq!(EMPTY, b"");
+        assert_eq!(SINGLE, b"abc");
+    }
+
+    #[test]
+    fn cstr() {
+        const EXISTING: &CStr = to_cstr(b"lib\0");
+        const NAME: &str = "example";
+        const PATH: &CStr = const_concat_cstr!(EXISTING, NAME, b".so", b'.', "1",);
+
+        assert_eq!(PATH.to_bytes_with_nul(), b"libexample.so.1\0");
+        assert_eq!(const_concat_cstr!().to_bytes_with_nul(), b"\0");
+    }
+
+    #[test]
+    #[should_panic(expected = "contains a NUL byte")]
+    fn cstr_interior_nul() {
+        to_cstr(b"a\0b\0");
+    }
+}
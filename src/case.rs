@@ -0,0 +1,345 @@
+//! Changing the case of strings in constants.
+//!
+//! Letters are mapped like `char::to_uppercase` and `char::to_lowercase`, the full Unicode case
+//! mappings, but only where those give a single char. A char whose mapping is more than one
+//! char is left unchanged, even if Unicode gives it a different simple mapping: `ß` stays `ß`,
+//! and `İ` stays `İ` rather than becoming `i`.
+
+mod tables;
+
+use crate::utf8::decode;
+
+/// The version of Unicode that the case mappings follow, like `char::UNICODE_VERSION`.
+pub const UNICODE_VERSION: (u8, u8, u8) = tables::UNICODE_VERSION;
+
+/// What [`convert`] changes a string to.
+///
+/// The identifier styles split the string into words at every char that isn't a letter or digit,
+/// before an uppercase letter that follows a lowercase letter or digit, and before the last
+/// uppercase letter of a run followed by a lowercase letter, so `"HTTPServer_error"` is the
+/// words `HTTP`, `Server` and `error`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Case {
+    /// `UPPERCASE`, changing only letters.
+    Upper,
+    /// `lowercase`, changing only letters.
+    Lower,
+    /// `snake_case`.
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`.
+    ScreamingSnake,
+    /// `kebab-case`.
+    Kebab,
+    /// `camelCase`.
+    Camel,
+    /// `PascalCase`.
+    Pascal,
+}
+
+/// Returns the uppercase of `c`, or `c` if its full uppercase mapping isn't a single char.
+pub const fn to_upper(c: char) -> char {
+    map(tables::UPPERCASE, c)
+}
+
+/// Returns the lowercase of `c`, or `c` if its full lowercase mapping isn't a single char.
+pub const fn to_lower(c: char) -> char {
+    map(tables::LOWERCASE, c)
+}
+
+const fn map(table: &[(u32, u32, i32, u32)], c: char) -> char {
+    let c = c as u32;
+
+    // Find the last run starting at or before `c`.
+    let (mut lo, mut hi) = (0, table.len());
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if table[mid].0 <= c {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    if lo > 0 {
+        let (first, last, delta, stride) = table[lo - 1];
+        if c <= last && (c - first) % stride == 0 {
+            if let Some(mapped) = char::from_u32(c.wrapping_add_signed(delta)) {
+                return mapped;
+            }
+        }
+    }
+
+    match char::from_u32(c) {
+        Some(c) => c,
+        None => unreachable!(),
+    }
+}
+
+/// Converts `s` to `case` into a `[u8; N]`.
+///
+/// Panics if `N` isn't [`converted_len`] of the same arguments.
+pub const fn convert<const N: usize>(s: &str, case: Case) -> [u8; N] {
+    let (out, len) = convert_into::<N>(s, case);
+    if len != N {
+        panic!("const_to_case: output length `N` is not the length of the converted string");
+    }
+
+    out
+}
+
+/// Returns the length of `s` converted to `case`.
+pub const fn converted_len(s: &str, case: Case) -> usize {
+    convert_into::<0>(s, case).1
+}
+
+/// Writes the first `N` bytes of the converted string and returns the length of all of it.
+const fn convert_into<const N: usize>(s: &str, case: Case) -> ([u8; N], usize) {
+    let bytes = s.as_bytes();
+    let mut out = [0u8; N];
+    let mut len = 0;
+
+    let separator: &[u8] = match case {
+        Case::Upper | Case::Lower => {
+            let upper = matches!(case, Case::Upper);
+            let mut i = 0;
+            while i < bytes.len() {
+                let (c, next) = decode(bytes, i);
+                (out, len) = write_char(out, len, if upper { to_upper(c) } else { to_lower(c) });
+                i = next;
+            }
+
+            return (out, len);
+        }
+        Case::Snake | Case::ScreamingSnake => b"_",
+        Case::Kebab => b"-",
+        Case::Camel | Case::Pascal => b"",
+    };
+
+    let mut i = 0;
+    let mut words = 0;
+    while i < bytes.len() {
+        let (c, next) = decode(bytes, i);
+        if !is_word_char(c) {
+            i = next;
+            continue;
+        }
+
+        if words > 0 {
+            (out, len) = crate::write_truncated(out, len, separator);
+        }
+
+        let mut first = true;
+        let (mut c, mut next) = (c, next);
+        loop {
+            let capitalize = match case {
+                Case::ScreamingSnake => true,
+                Case::Camel => first && words > 0,
+                Case::Pascal => first,
+                _ => false,
+            };
+            (out, len) = write_char(out, len, if capitalize { to_upper(c) } else { to_lower(c) });
+
+            if next == bytes.len() {
+                i = next;
+                break;
+            }
+
+            let (following, after) = decode(bytes, next);
+            let lookahead = if after < bytes.len() { Some(decode(bytes, after).0) } else { None };
+            if !is_word_char(following) || starts_word(c, following, lookahead) {
+                i = next;
+                break;
+            }
+
+            (c, next) = (following, after);
+            first = false;
+        }
+
+        words += 1;
+    }
+
+    (out, len)
+}
+
+/// Returns `true` if a word starts at `c`, given the char before it and the one after it.
+const fn starts_word(prev: char, c: char, next: Option<char>) -> bool {
+    if !is_upper(c) {
+        return false;
+    }
+
+    if is_lower(prev) || prev.is_ascii_digit() {
+        return true;
+    }
+
+    match next {
+        Some(next) => is_upper(prev) && is_lower(next),
+        None => false,
+    }
+}
+
+/// Returns `true` for letters and digits, like `char::is_alphanumeric`.
+const fn is_word_char(c: char) -> bool {
+    if c.is_ascii() {
+        return c.is_ascii_alphanumeric();
+    }
+
+    let table = tables::ALPHANUMERIC;
+    let c = c as u32;
+    let (mut lo, mut hi) = (0, table.len());
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if table[mid].0 <= c {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo > 0 && c <= table[lo - 1].1
+}
+
+const fn is_upper(c: char) -> bool {
+    to_lower(c) as u32 != c as u32
+}
+
+const fn is_lower(c: char) -> bool {
+    to_upper(c) as u32 != c as u32
+}
+
+const fn write_char<const N: usize>(out: [u8; N], len: usize, c: char) -> ([u8; N], usize) {
+    let mut buf = [0u8; 4];
+    crate::write_truncated(out, len, c.encode_utf8(&mut buf).as_bytes())
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_convert_case {
+    ($s:expr, $case:ident) => {{
+        const __S: &str = $s;
+        const __LEN: usize = $crate::case::converted_len(__S, $crate::case::Case::$case);
+        const __BYTES: [u8; __LEN] = $crate::case::convert::<__LEN>(__S, $crate::case::Case::$case);
+
+        unsafe { $crate::from_utf8_unchecked(&__BYTES) }
+    }};
+}
+
+/// Converts a constant string to uppercase, into a `&'static str`.
+#[macro_export]
+macro_rules! const_to_upper {
+    ($s:expr) => {
+        $crate::__const_convert_case!($s, Upper)
+    };
+}
+
+/// Converts a constant string to lowercase, into a `&'static str`.
+#[macro_export]
+macro_rules! const_to_lower {
+    ($s:expr) => {
+        $crate::__const_convert_case!($s, Lower)
+    };
+}
+
+/// Converts a constant string to `snake_case`, into a `&'static str`.
+#[macro_export]
+macro_rules! const_to_snake_case {
+    ($s:expr) => {
+        $crate::__const_convert_case!($s, Snake)
+    };
+}
+
+/// Converts a constant string to `SCREAMING_SNAKE_CASE`, into a `&'static str`.
+#[macro_export]
+macro_rules! const_to_screaming_snake_case {
+    ($s:expr) => {
+        $crate::__const_convert_case!($s, ScreamingSnake)
+    };
+}
+
+/// Converts a constant string to `kebab-case`, into a `&'static str`.
+#[macro_export]
+macro_rules! const_to_kebab_case {
+    ($s:expr) => {
+        $crate::__const_convert_case!($s, Kebab)
+    };
+}
+
+/// Converts a constant string to `camelCase`, into a `&'static str`.
+#[macro_export]
+macro_rules! const_to_camel_case {
+    ($s:expr) => {
+        $crate::__const_convert_case!($s, Camel)
+    };
+}
+
+/// Converts a constant string to `PascalCase`, into a `&'static str`.
+#[macro_export]
+macro_rules! const_to_pascal_case {
+    ($s:expr) => {
+        $crate::__const_convert_case!($s, Pascal)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_lower, to_upper};
+    use crate::const_concat;
+
+    #[test]
+    fn chars_match_std() {
+        // The tables are a snapshot of one Unicode version, so they can only be compared with a
+        // standard library that uses the same one. `examples/gen_case_tables.rs` regenerates them.
+        if char::UNICODE_VERSION != super::UNICODE_VERSION {
+            eprintln!(
+                "skipping: case tables are for Unicode {:?}, std uses {:?}",
+                super::UNICODE_VERSION,
+                char::UNICODE_VERSION
+            );
+            return;
+        }
+
+        fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+            let c = chars.next()?;
+            chars.next().map_or(Some(c), |_| None)
+        }
+
+        for c in (0..=0x10ffff).filter_map(char::from_u32) {
+            assert_eq!(to_upper(c), single(c.to_uppercase()).unwrap_or(c), "{:?}", c);
+            assert_eq!(to_lower(c), single(c.to_lowercase()).unwrap_or(c), "{:?}", c);
+            assert_eq!(super::is_word_char(c), c.is_alphanumeric(), "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn upper_and_lower() {
+        const NAME: &str = "Straße Ωmega-ǅ 1";
+
+        assert_eq!(const_to_upper!(NAME), "STRAßE ΩMEGA-Ǆ 1");
+        assert_eq!(const_to_lower!(NAME), "straße ωmega-ǆ 1");
+        assert_eq!(const_to_upper!(""), "");
+        assert_eq!(const_to_lower!("İ"), "İ");
+        assert_eq!(const_to_upper!("ᾳ"), "ᾳ");
+    }
+
+    #[test]
+    fn identifier_styles() {
+        const PREFIX: &str = "myApp";
+        const NAME: &str = const_concat!(PREFIX, " HTTPServer_error-code2Fix");
+
+        assert_eq!(const_to_snake_case!(NAME), "my_app_http_server_error_code2_fix");
+        assert_eq!(const_to_screaming_snake_case!(NAME), "MY_APP_HTTP_SERVER_ERROR_CODE2_FIX");
+        assert_eq!(const_to_kebab_case!(NAME), "my-app-http-server-error-code2-fix");
+        assert_eq!(const_to_camel_case!(NAME), "myAppHttpServerErrorCode2Fix");
+        assert_eq!(const_to_pascal_case!(NAME), "MyAppHttpServerErrorCode2Fix");
+        assert_eq!(const_to_snake_case!("__ÉtéBrûlant__"), "été_brûlant");
+        assert_eq!(const_to_pascal_case!("--"), "");
+    }
+
+    #[test]
+    fn non_ascii_separators() {
+        const NAME: &str = "foo—bar\u{a0}baz…qux «Ünïcode»\u{3000}名前";
+
+        assert_eq!(const_to_snake_case!(NAME), "foo_bar_baz_qux_ünïcode_名前");
+        assert_eq!(const_to_screaming_snake_case!("naïve—café"), "NAÏVE_CAFÉ");
+        assert_eq!(const_to_pascal_case!("x\u{2028}y·z"), "XYZ");
+    }
+}
@@ -0,0 +1,175 @@
+use std::{fmt, ops::Deref};
+
+/// A string with a fixed capacity of `N` bytes that can be built up in `const fn`s.
+///
+/// ```
+/// use const_concat::ConstStr;
+///
+/// const fn greeting(name: &str) -> ConstStr<32> {
+///     let mut s = ConstStr::new();
+///     s.push_str("Hello, ");
+///     s.push_str(name);
+///     s.push_char('!');
+///     s
+/// }
+///
+/// const GREETING: ConstStr<32> = greeting("world");
+///
+/// assert_eq!(GREETING, "Hello, world!");
+/// ```
+#[derive(Copy, Clone)]
+pub struct ConstStr<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ConstStr<N> {
+    /// Creates an empty string.
+    pub const fn new() -> Self {
+        ConstStr { bytes: [0; N], len: 0 }
+    }
+
+    /// Creates a string from the concatenation of `parts`.
+    ///
+    /// Panics if the result doesn't fit in `N` bytes.
+    pub const fn concat(parts: &[&str]) -> Self {
+        let mut s = Self::new();
+        let mut i = 0;
+        while i < parts.len() {
+            s.push_str(parts[i]);
+            i += 1;
+        }
+
+        s
+    }
+
+    /// Appends `s`.
+    ///
+    /// Panics if the result doesn't fit in `N` bytes.
+    pub const fn push_str(&mut self, s: &str) {
+        let s = s.as_bytes();
+        if s.len() > N - self.len {
+            panic!("ConstStr: capacity exceeded");
+        }
+
+        let mut i = 0;
+        while i < s.len() {
+            self.bytes[self.len] = s[i];
+            self.len += 1;
+            i += 1;
+        }
+    }
+
+    /// Appends `c`.
+    ///
+    /// Panics if the result doesn't fit in `N` bytes.
+    pub const fn push_char(&mut self, c: char) {
+        let mut buf = [0u8; 4];
+        self.push_str(c.encode_utf8(&mut buf));
+    }
+
+    /// Returns the length in bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the string is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum length in bytes, `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the contents as a `&str`.
+    pub const fn as_str(&self) -> &str {
+        unsafe {
+            let bytes = std::slice::from_raw_parts(self.bytes.as_ptr(), self.len);
+            crate::from_utf8_unchecked(bytes)
+        }
+    }
+}
+
+impl<const N: usize> Default for ConstStr<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for ConstStr<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Display for ConstStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for ConstStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<ConstStr<M>> for ConstStr<N> {
+    fn eq(&self, other: &ConstStr<M>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for ConstStr<N> {}
+
+impl<const N: usize> PartialEq<str> for ConstStr<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a, const N: usize> PartialEq<&'a str> for ConstStr<N> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConstStr;
+
+    #[test]
+    fn build_in_const_fn() {
+        const fn path(segments: &[&str]) -> ConstStr<64> {
+            let mut s = ConstStr::new();
+            let mut i = 0;
+            while i < segments.len() {
+                s.push_char('/');
+                s.push_str(segments[i]);
+                i += 1;
+            }
+            s
+        }
+
+        const PATH: ConstStr<64> = path(&["usr", "lïb"]);
+        const EMPTY: ConstStr<8> = ConstStr::new();
+
+        assert_eq!(PATH, "/usr/lïb");
+        assert_eq!(PATH.len(), 9);
+        assert!(PATH.starts_with("/usr"));
+        assert!(EMPTY.is_empty());
+        assert_eq!(PATH.to_string(), "/usr/lïb");
+        assert_eq!(format!("{:?}", PATH), "\"/usr/lïb\"");
+        assert_eq!(PATH, ConstStr::<9>::concat(&["/usr", "/lïb"]));
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity exceeded")]
+    fn capacity_exceeded() {
+        ConstStr::<4>::concat(&["ab", "cde"]);
+    }
+}
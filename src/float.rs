@@ -0,0 +1,702 @@
+//! Formatting `f32` and `f64` in constants, the same way as `Display`.
+//!
+//! The shortest output is generated with the Dragon4 algorithm as used by `core`, and fixed
+//! precision output is rounded exactly, with ties to even. Both work on big integers, so they
+//! give the same results as `format!` for every value.
+
+use std::cmp::Ordering;
+
+/// A float split into its sign and value, so that `f32` and `f64` can share the formatting code.
+#[derive(Clone, Copy)]
+pub struct Decoded {
+    negative: bool,
+    kind: Kind,
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Nan,
+    Infinite,
+    Zero,
+    /// `mant * 2^exp`, where `boundary` is set for powers of two, which are closer to the float
+    /// below than the float above.
+    Finite { mant: u64, exp: i32, normal: bool, boundary: bool },
+}
+
+impl Decoded {
+    pub const fn from_f32(x: f32) -> Self {
+        let bits = x.to_bits();
+        let exp = ((bits >> 23) & 0xff) as i32;
+        let frac = (bits & 0x7f_ffff) as u64;
+
+        Decoded { negative: bits >> 31 != 0, kind: decode(exp, frac, 0xff, 23, 150) }
+    }
+
+    pub const fn from_f64(x: f64) -> Self {
+        let bits = x.to_bits();
+        let exp = ((bits >> 52) & 0x7ff) as i32;
+        let frac = bits & 0xf_ffff_ffff_ffff;
+
+        Decoded { negative: bits >> 63 != 0, kind: decode(exp, frac, 0x7ff, 52, 1075) }
+    }
+}
+
+/// Splits the exponent and fraction fields into the same `mant * 2^exp` as `integer_decode`.
+const fn decode(exp: i32, frac: u64, max_exp: i32, frac_bits: u32, bias: i32) -> Kind {
+    if exp == max_exp {
+        if frac == 0 {
+            Kind::Infinite
+        } else {
+            Kind::Nan
+        }
+    } else if exp == 0 {
+        if frac == 0 {
+            Kind::Zero
+        } else {
+            Kind::Finite { mant: frac << 1, exp: -bias, normal: false, boundary: false }
+        }
+    } else {
+        Kind::Finite {
+            mant: frac | (1 << frac_bits),
+            exp: exp - bias,
+            normal: true,
+            boundary: frac == 0,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct FloatArg<T>(pub T);
+
+impl FloatArg<f32> {
+    pub const fn decode(self) -> Decoded {
+        Decoded::from_f32(self.0)
+    }
+}
+
+impl FloatArg<f64> {
+    pub const fn decode(self) -> Decoded {
+        Decoded::from_f64(self.0)
+    }
+}
+
+/// Returns the length of `x` formatted with `{}`.
+pub const fn shortest_len(x: Decoded) -> usize {
+    write_shortest::<0>(x).1
+}
+
+/// Formats `x` with `{}` into a `[u8; N]`: the fewest digits that parse back to the same value,
+/// without an exponent.
+///
+/// Panics if `N` isn't [`shortest_len`] of `x`.
+pub const fn shortest<const N: usize>(x: Decoded) -> [u8; N] {
+    let (out, len) = write_shortest::<N>(x);
+    if len != N {
+        panic!("const_fmt_float: output length `N` is not the formatted length");
+    }
+
+    out
+}
+
+/// Returns the length of `x` formatted with `{:.precision}`.
+pub const fn fixed_len(x: Decoded, precision: usize) -> usize {
+    write_fixed::<0>(x, precision).1
+}
+
+/// Formats `x` with `{:.precision}` into a `[u8; N]`: exactly `precision` digits after the
+/// decimal point, rounded to nearest with ties to even.
+///
+/// Panics if `N` isn't [`fixed_len`] of `x`.
+pub const fn fixed<const N: usize>(x: Decoded, precision: usize) -> [u8; N] {
+    let (out, len) = write_fixed::<N>(x, precision);
+    if len != N {
+        panic!("const_fmt_float: output length `N` is not the formatted length");
+    }
+
+    out
+}
+
+/// Writes the first `N` bytes of `x` formatted with `{}` and returns the length of all of it.
+pub(crate) const fn write_shortest<const N: usize>(x: Decoded) -> ([u8; N], usize) {
+    let out = [0u8; N];
+    let (mant, exp, normal, boundary) = match x.kind {
+        Kind::Nan => return crate::write_truncated(out, 0, b"NaN"),
+        Kind::Finite { mant, exp, normal, boundary } => (mant, exp, normal, boundary),
+        _ => return write_special(x),
+    };
+
+    let (mut out, mut len) = write_sign(x);
+    let (digits, count, k) = shortest_digits(mant, exp, normal, boundary);
+    let digits = digits.split_at(count).0;
+
+    if k <= 0 {
+        (out, len) = crate::write_truncated(out, len, b"0.");
+        (out, len) = write_zeros(out, len, -k as usize);
+        crate::write_truncated(out, len, digits)
+    } else if (k as usize) < count {
+        let (int, frac) = digits.split_at(k as usize);
+        (out, len) = crate::write_truncated(out, len, int);
+        (out, len) = crate::write_truncated(out, len, b".");
+        crate::write_truncated(out, len, frac)
+    } else {
+        (out, len) = crate::write_truncated(out, len, digits);
+        write_zeros(out, len, k as usize - count)
+    }
+}
+
+const fn write_fixed<const N: usize>(x: Decoded, precision: usize) -> ([u8; N], usize) {
+    let out = [0u8; N];
+    let (n, frac_digits) = match x.kind {
+        Kind::Nan => return crate::write_truncated(out, 0, b"NaN"),
+        Kind::Infinite => return write_special(x),
+        Kind::Zero => (Big::from_u64(0), 0),
+        Kind::Finite { mant, exp, .. } => {
+            let mut n = Big::from_u64(mant);
+            if exp >= 0 {
+                n.mul_pow2(exp as usize);
+                (n, 0)
+            } else {
+                // `mant * 2^exp * 10^digits` is `mant * 5^digits / 2^(-exp - digits)`, and
+                // there's no need for more than `-exp` digits, since the rest are zeros.
+                let digits = if precision < -exp as usize { precision } else { -exp as usize };
+                let shift = -exp as usize - digits;
+                n.mul_pow5(digits);
+
+                let round_up = shift > 0
+                    && n.bit(shift - 1)
+                    && (n.any_below(shift - 1) || n.bit(shift));
+                n.shr(shift);
+                if round_up {
+                    n.add(&Big::from_u64(1));
+                }
+                (n, digits)
+            }
+        }
+    };
+
+    let (mut out, mut len) = write_sign(x);
+    let (digits, start) = to_decimal(n);
+    let digits = digits.split_at(start).1;
+
+    if digits.len() <= frac_digits {
+        (out, len) = crate::write_truncated(out, len, b"0");
+        if frac_digits > 0 {
+            (out, len) = crate::write_truncated(out, len, b".");
+        }
+        (out, len) = write_zeros(out, len, frac_digits - digits.len());
+        (out, len) = crate::write_truncated(out, len, digits);
+    } else {
+        let (int, frac) = digits.split_at(digits.len() - frac_digits);
+        (out, len) = crate::write_truncated(out, len, int);
+        if frac_digits > 0 {
+            (out, len) = crate::write_truncated(out, len, b".");
+        }
+        (out, len) = crate::write_truncated(out, len, frac);
+    }
+    if precision > frac_digits {
+        if frac_digits == 0 {
+            (out, len) = crate::write_truncated(out, len, b".");
+        }
+        (out, len) = write_zeros(out, len, precision - frac_digits);
+    }
+
+    (out, len)
+}
+
+/// Writes a zero or infinity, which are the same for both kinds of formatting apart from the
+/// fractional zeros.
+const fn write_special<const N: usize>(x: Decoded) -> ([u8; N], usize) {
+    let (out, len) = write_sign(x);
+    match x.kind {
+        Kind::Infinite => crate::write_truncated(out, len, b"inf"),
+        _ => crate::write_truncated(out, len, b"0"),
+    }
+}
+
+const fn write_sign<const N: usize>(x: Decoded) -> ([u8; N], usize) {
+    crate::write_truncated([0u8; N], 0, if x.negative { b"-" } else { b"" })
+}
+
+const fn write_zeros<const N: usize>(
+    mut out: [u8; N],
+    mut len: usize,
+    n: usize,
+) -> ([u8; N], usize) {
+    let mut i = 0;
+    while i < n {
+        (out, len) = crate::write_truncated(out, len, b"0");
+        i += 1;
+    }
+
+    (out, len)
+}
+
+/// 17 significant digits are enough for any `f64`, plus one in case rounding up carries all the
+/// way.
+const MAX_SIG_DIGITS: usize = 18;
+
+/// Returns the shortest digits `d` and exponent `k` such that `0.d * 10^k` rounds to the float
+/// `mant * 2^exp`, the same way as `core::num::flt2dec::strategy::dragon::format_shortest`.
+const fn shortest_digits(
+    mant: u64,
+    exp: i32,
+    normal: bool,
+    boundary: bool,
+) -> ([u8; MAX_SIG_DIGITS], usize, i32) {
+    // The value is `mant * 2^exp`, and anything between `(mant - minus) * 2^exp` and
+    // `(mant + plus) * 2^exp` rounds to it, including the bounds if the mantissa is even.
+    let (d_mant, d_minus, d_plus, d_exp) = if boundary {
+        (mant << 2, 1, 2, exp - 2)
+    } else if normal {
+        (mant << 1, 1, 1, exp - 1)
+    } else {
+        (mant, 1, 1, exp)
+    };
+    let rounding = (if mant & 1 == 0 { Ordering::Greater } else { Ordering::Equal }) as i8;
+
+    let mut k = estimate_scaling_factor(d_mant + d_plus, d_exp);
+
+    let mut mant = Big::from_u64(d_mant);
+    let mut minus = Big::from_u64(d_minus);
+    let mut plus = Big::from_u64(d_plus);
+    let mut scale = Big::from_u64(1);
+    if d_exp < 0 {
+        scale.mul_pow2(-d_exp as usize);
+    } else {
+        mant.mul_pow2(d_exp as usize);
+        minus.mul_pow2(d_exp as usize);
+        plus.mul_pow2(d_exp as usize);
+    }
+
+    if k >= 0 {
+        scale.mul_pow10(k as usize);
+    } else {
+        mant.mul_pow10(-k as usize);
+        minus.mul_pow10(-k as usize);
+        plus.mul_pow10(-k as usize);
+    }
+
+    // Fix up the estimate so that `scale < mant + plus <= scale * 10`.
+    let mut high = mant;
+    high.add(&plus);
+    if (scale.cmp(&high) as i8) < rounding {
+        k += 1;
+    } else {
+        mant.mul_small(10);
+        minus.mul_small(10);
+        plus.mul_small(10);
+    }
+
+    let mut scale2 = scale;
+    scale2.mul_pow2(1);
+    let mut scale4 = scale;
+    scale4.mul_pow2(2);
+    let mut scale8 = scale;
+    scale8.mul_pow2(3);
+
+    let mut digits = [0u8; MAX_SIG_DIGITS];
+    let mut count = 0;
+    let (down, up) = loop {
+        let mut d = 0;
+        if !mant.lt(&scale8) {
+            mant.sub(&scale8);
+            d += 8;
+        }
+        if !mant.lt(&scale4) {
+            mant.sub(&scale4);
+            d += 4;
+        }
+        if !mant.lt(&scale2) {
+            mant.sub(&scale2);
+            d += 2;
+        }
+        if !mant.lt(&scale) {
+            mant.sub(&scale);
+            d += 1;
+        }
+        digits[count] = b'0' + d;
+        count += 1;
+
+        let mut high = mant;
+        high.add(&plus);
+        let down = (mant.cmp(&minus) as i8) < rounding;
+        let up = (scale.cmp(&high) as i8) < rounding;
+        if down || up {
+            break (down, up);
+        }
+
+        mant.mul_small(10);
+        minus.mul_small(10);
+        plus.mul_small(10);
+    };
+
+    // Round up if the remainder is more than half a digit, or exactly half and the digit is odd.
+    mant.mul_pow2(1);
+    if up && (!down || !mant.lt(&scale)) {
+        let mut i = count;
+        while i > 0 && digits[i - 1] == b'9' {
+            digits[i - 1] = b'0';
+            i -= 1;
+        }
+        if i > 0 {
+            digits[i - 1] += 1;
+        } else {
+            digits[0] = b'1';
+            digits[count] = b'0';
+            count += 1;
+            k += 1;
+        }
+    }
+
+    (digits, count, k)
+}
+
+/// Returns `k` such that `10^(k - 1) < mant * 2^exp <= 10^(k + 1)`.
+const fn estimate_scaling_factor(mant: u64, exp: i32) -> i32 {
+    let bits = 64 - (mant - 1).leading_zeros() as i64;
+    // 1292913986 is `floor(2^32 * log10(2))`, so this underestimates slightly, if anything.
+    (((bits + exp as i64) * 1292913986) >> 32) as i32
+}
+
+/// Enough decimal digits for the biggest [`Big`].
+const MAX_DIGITS: usize = 780;
+
+/// Returns the decimal digits of `n`, which start at the returned index.
+const fn to_decimal(mut n: Big) -> ([u8; MAX_DIGITS], usize) {
+    let mut digits = [b'0'; MAX_DIGITS];
+    let mut end = MAX_DIGITS;
+    while !n.is_zero() {
+        let mut chunk = n.div_rem_small(1_000_000_000);
+        let mut i = 0;
+        while i < 9 {
+            end -= 1;
+            digits[end] = b'0' + (chunk % 10) as u8;
+            chunk /= 10;
+            i += 1;
+        }
+    }
+
+    let mut start = end;
+    while start < MAX_DIGITS - 1 && digits[start] == b'0' {
+        start += 1;
+    }
+
+    (digits, start)
+}
+
+/// Enough 32-bit limbs for `mant * 5^1075`, the most that fixed precision formatting of the
+/// smallest `f64` can need.
+const LIMBS: usize = 80;
+
+/// A fixed-size unsigned big integer, with its limbs least significant first.
+#[derive(Clone, Copy)]
+struct Big {
+    limbs: [u32; LIMBS],
+}
+
+impl Big {
+    const fn from_u64(n: u64) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = n as u32;
+        limbs[1] = (n >> 32) as u32;
+        Big { limbs }
+    }
+
+    const fn is_zero(&self) -> bool {
+        let mut i = 0;
+        while i < LIMBS {
+            if self.limbs[i] != 0 {
+                return false;
+            }
+            i += 1;
+        }
+
+        true
+    }
+
+    const fn bit(&self, i: usize) -> bool {
+        (self.limbs[i / 32] >> (i % 32)) & 1 != 0
+    }
+
+    /// Returns whether any of the bits below bit `i` are set.
+    const fn any_below(&self, i: usize) -> bool {
+        let mut limb = 0;
+        while limb < i / 32 {
+            if self.limbs[limb] != 0 {
+                return true;
+            }
+            limb += 1;
+        }
+
+        self.limbs[i / 32] & ((1 << (i % 32)) - 1) != 0
+    }
+
+    const fn cmp(&self, other: &Big) -> Ordering {
+        let mut i = LIMBS;
+        while i > 0 {
+            i -= 1;
+            if self.limbs[i] != other.limbs[i] {
+                return if self.limbs[i] < other.limbs[i] {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                };
+            }
+        }
+
+        Ordering::Equal
+    }
+
+    const fn lt(&self, other: &Big) -> bool {
+        matches!(self.cmp(other), Ordering::Less)
+    }
+
+    const fn add(&mut self, other: &Big) {
+        let mut carry = 0;
+        let mut i = 0;
+        while i < LIMBS {
+            let sum = self.limbs[i] as u64 + other.limbs[i] as u64 + carry;
+            self.limbs[i] = sum as u32;
+            carry = sum >> 32;
+            i += 1;
+        }
+    }
+
+    /// Subtracts `other`, which must not be bigger than `self`.
+    const fn sub(&mut self, other: &Big) {
+        let mut borrow = 0;
+        let mut i = 0;
+        while i < LIMBS {
+            let diff = self.limbs[i] as i64 - other.limbs[i] as i64 - borrow;
+            self.limbs[i] = diff as u32;
+            borrow = (diff < 0) as i64;
+            i += 1;
+        }
+    }
+
+    const fn mul_small(&mut self, m: u32) {
+        let mut carry = 0;
+        let mut i = 0;
+        while i < LIMBS {
+            let product = self.limbs[i] as u64 * m as u64 + carry;
+            self.limbs[i] = product as u32;
+            carry = product >> 32;
+            i += 1;
+        }
+    }
+
+    const fn mul_pow2(&mut self, bits: usize) {
+        let words = bits / 32;
+        let shift = bits % 32;
+        let mut i = LIMBS;
+        while i > 0 {
+            i -= 1;
+            let mut limb = 0;
+            if i >= words {
+                limb = self.limbs[i - words] << shift;
+                if shift > 0 && i > words {
+                    limb |= self.limbs[i - words - 1] >> (32 - shift);
+                }
+            }
+            self.limbs[i] = limb;
+        }
+    }
+
+    const fn shr(&mut self, bits: usize) {
+        let words = bits / 32;
+        let shift = bits % 32;
+        let mut i = 0;
+        while i < LIMBS {
+            let mut limb = 0;
+            if i + words < LIMBS {
+                limb = self.limbs[i + words] >> shift;
+                if shift > 0 && i + words + 1 < LIMBS {
+                    limb |= self.limbs[i + words + 1] << (32 - shift);
+                }
+            }
+            self.limbs[i] = limb;
+            i += 1;
+        }
+    }
+
+    const fn mul_pow5(&mut self, mut n: usize) {
+        // The biggest power of five that fits in a `u32`.
+        const POW5_13: u32 = 1_220_703_125;
+        while n >= 13 {
+            self.mul_small(POW5_13);
+            n -= 13;
+        }
+        self.mul_small(5u32.pow(n as u32));
+    }
+
+    const fn mul_pow10(&mut self, n: usize) {
+        self.mul_pow5(n);
+        self.mul_pow2(n);
+    }
+
+    /// Divides by `d` in place and returns the remainder.
+    const fn div_rem_small(&mut self, d: u32) -> u32 {
+        let mut rem = 0u64;
+        let mut i = LIMBS;
+        while i > 0 {
+            i -= 1;
+            let cur = (rem << 32) | self.limbs[i] as u64;
+            self.limbs[i] = (cur / d as u64) as u32;
+            rem = cur % d as u64;
+        }
+
+        rem as u32
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_fmt_float {
+    ($x:expr, $len:ident, $write:ident $(, $precision:expr)*) => {{
+        const __X: $crate::float::Decoded = $crate::float::FloatArg($x).decode();
+        const __LEN: usize = $crate::float::$len(__X $(, $precision)*);
+        const __OUT: [u8; __LEN] = $crate::float::$write::<__LEN>(__X $(, $precision)*);
+
+        unsafe { $crate::from_utf8_unchecked(&__OUT) }
+    }};
+}
+
+/// Formats a constant `f32` or `f64` into a `&'static str`, the same way as `format!`.
+///
+/// `const_fmt_float!(X)` is `format!("{}", X)`, and `const_fmt_float!(X, precision = 2)` is
+/// `format!("{:.2}", X)`. Floats can also be passed straight to [`const_concat!`], which formats
+/// them like the first form. Float literals need a suffix (`1.5f64`) so that their type is known.
+#[macro_export]
+macro_rules! const_fmt_float {
+    ($x:expr) => {
+        $crate::__const_fmt_float!($x, shortest_len, shortest)
+    };
+    ($x:expr, precision = $precision:expr) => {
+        $crate::__const_fmt_float!($x, fixed_len, fixed, $precision)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixed, fixed_len, shortest, shortest_len, write_shortest, Decoded};
+    use crate::const_concat;
+
+    fn f64_values() -> Vec<f64> {
+        let mut values = vec![
+            0.0,
+            -0.0,
+            1.0,
+            -1.5,
+            0.1,
+            0.3,
+            1.0 / 3.0,
+            2.5,
+            0.125,
+            100.0,
+            1e15,
+            1e16,
+            1e21,
+            123456789.125,
+            f64::MAX,
+            f64::MIN,
+            f64::MIN_POSITIVE,
+            f64::EPSILON,
+            5e-324,
+            -5e-324,
+            f64::from_bits(0x000f_ffff_ffff_ffff),
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+            -f64::NAN,
+        ];
+
+        // A simple xorshift generator, so that the bit patterns cover every exponent.
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..2000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            values.push(f64::from_bits(state));
+        }
+        for i in -30..30 {
+            values.push(10f64.powi(i));
+            values.push(2f64.powi(i * 30));
+        }
+
+        values
+    }
+
+    fn display(x: Decoded) -> String {
+        let (buf, len) = write_shortest::<400>(x);
+        assert_eq!(len, shortest_len(x));
+        String::from_utf8(buf[..len].to_vec()).unwrap()
+    }
+
+    fn display_fixed(x: Decoded, precision: usize) -> String {
+        let (buf, len) = super::write_fixed::<1200>(x, precision);
+        assert_eq!(len, fixed_len(x, precision));
+        String::from_utf8(buf[..len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn shortest_matches_display() {
+        for x in f64_values() {
+            assert_eq!(display(Decoded::from_f64(x)), x.to_string(), "{:e}", x);
+            let x = x as f32;
+            assert_eq!(display(Decoded::from_f32(x)), x.to_string(), "{:e}", x);
+        }
+
+        let mut state = 0x9e37_79b9u32;
+        for _ in 0..2000 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let x = f32::from_bits(state);
+            assert_eq!(display(Decoded::from_f32(x)), x.to_string(), "{:e}", x);
+        }
+    }
+
+    #[test]
+    fn fixed_matches_display() {
+        for x in f64_values() {
+            for precision in [0, 1, 2, 3, 6, 17, 30] {
+                let expected = format!("{:.*}", precision, x);
+                assert_eq!(display_fixed(Decoded::from_f64(x), precision), expected, "{:e}", x);
+                let x = x as f32;
+                let expected = format!("{:.*}", precision, x);
+                assert_eq!(display_fixed(Decoded::from_f32(x), precision), expected, "{:e}", x);
+            }
+        }
+
+        // Exact ties round to even.
+        for (x, precision) in [(0.5, 0), (1.5, 0), (2.5, 0), (0.125, 2), (0.375, 2), (-0.5, 0)] {
+            let expected = format!("{:.*}", precision, x);
+            assert_eq!(display_fixed(Decoded::from_f64(x), precision), expected);
+        }
+        let smallest = Decoded::from_f64(5e-324);
+        assert_eq!(display_fixed(smallest, 1100), format!("{:.1100}", 5e-324));
+        assert_eq!(fixed::<4>(Decoded::from_f64(1.005), 2), *b"1.00");
+    }
+
+    #[test]
+    fn constants() {
+        const G: f64 = 9.80665;
+        const RATIO: f32 = 0.75;
+        const LABEL: &str = const_concat!("g = ", G, " m/s², ratio ", RATIO);
+        const ROUNDED: &str = const_fmt_float!(G, precision = 2);
+        const SHORTEST: &str = const_fmt_float!(1e-7f64);
+
+        assert_eq!(LABEL, "g = 9.80665 m/s², ratio 0.75");
+        assert_eq!(ROUNDED, "9.81");
+        assert_eq!(SHORTEST, "0.0000001");
+        assert_eq!(const_fmt_float!(-0.0f32), "-0");
+        assert_eq!(const_fmt_float!(f64::NAN, precision = 3), "NaN");
+        assert_eq!(const_fmt_float!(f32::NEG_INFINITY), "-inf");
+    }
+
+    #[test]
+    #[should_panic(expected = "const_fmt_float: output length `N` is not the formatted length")]
+    fn wrong_length() {
+        shortest::<2>(Decoded::from_f64(1.25));
+    }
+}
@@ -0,0 +1,564 @@
+/// Wrapper used by [`const_concat!`] to turn each of its arguments into a string.
+///
+/// Works like [`ByteArg`](crate::bytes::ByteArg), with `to_str` in place of `as_bytes`.
+pub struct Arg<T>(pub T);
+
+/// The text of a single [`Arg`], either borrowed from the argument or formatted into an inline
+/// buffer.
+///
+/// Byte string arguments are kept as bytes, which are only checked to be UTF-8 once they're
+/// converted to a string. Macros that join several arguments, like [`const_format!`], use the
+/// bytes and check their whole output instead, so a char can be split between byte strings.
+// These are only built in constants, where the buffer can't be boxed.
+#[allow(clippy::large_enum_variant)]
+#[derive(Copy, Clone)]
+pub enum Formatted<'a> {
+    Borrowed(&'a str),
+    Bytes(&'a [u8]),
+    Inline { buf: [u8; INLINE_CAP], start: usize },
+}
+
+/// Enough room for `-5e-324`, the longest `f64` when written out without an exponent.
+const INLINE_CAP: usize = 327;
+
+impl<'a> Formatted<'a> {
+    /// Panics if the argument was a byte string that isn't valid UTF-8, with the byte offset
+    /// in that argument of the first invalid sequence.
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Formatted::Borrowed(s) => s,
+            Formatted::Bytes(bytes) => match std::str::from_utf8(bytes) {
+                Ok(s) => s,
+                Err(err) => {
+                    let mut msg = crate::ConstStr::<96>::new();
+                    msg.push_str("invalid UTF-8 in byte string argument at byte offset ");
+                    msg.push_str(Arg(err.valid_up_to()).to_str().as_str());
+                    panic!("{}", msg.as_str())
+                }
+            },
+            Formatted::Inline { .. } => unsafe { crate::from_utf8_unchecked(self.as_bytes()) },
+        }
+    }
+
+    pub const fn as_bytes(&self) -> &[u8] {
+        match self {
+            Formatted::Borrowed(s) => s.as_bytes(),
+            Formatted::Bytes(bytes) => bytes,
+            Formatted::Inline { buf, start } => unsafe {
+                std::slice::from_raw_parts(buf.as_ptr().add(*start), INLINE_CAP - *start)
+            },
+        }
+    }
+}
+
+/// Concatenates `parts` for `const_concat!(capacity = N; ...)`.
+///
+/// Like [`format`], byte string parts are copied as they are and only the output is checked to be
+/// UTF-8. Panics if it isn't, or if it's longer than `N` bytes.
+pub const fn concat_with_capacity<const N: usize>(parts: &[Formatted]) -> crate::ConstStr<N> {
+    let mut out = [0u8; N];
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        (out, len) = crate::write_truncated(out, len, parts[i].as_bytes());
+        i += 1;
+    }
+    if len > N {
+        panic!("const_concat: output is longer than the given capacity");
+    }
+
+    crate::ConstStr::concat(&[crate::output_to_str("const_concat", out.split_at(len).0)])
+}
+
+const fn format_u128(mut n: u128, negative: bool) -> Formatted<'static> {
+    let mut buf = [0u8; INLINE_CAP];
+    let mut start = INLINE_CAP;
+
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+
+    if negative {
+        start -= 1;
+        buf[start] = b'-';
+    }
+
+    Formatted::Inline { buf, start }
+}
+
+impl<'a> Arg<&'a str> {
+    pub const fn to_str(self) -> Formatted<'a> {
+        Formatted::Borrowed(self.0)
+    }
+}
+
+impl<'a> Arg<Option<&'a str>> {
+    pub const fn to_str(self) -> Formatted<'a> {
+        match self.0 {
+            Some(s) => Formatted::Borrowed(s),
+            None => Formatted::Borrowed(""),
+        }
+    }
+}
+
+impl<'a> Arg<&'a [u8]> {
+    pub const fn to_str(self) -> Formatted<'a> {
+        Formatted::Bytes(self.0)
+    }
+}
+
+impl<'a, const N: usize> Arg<&'a [u8; N]> {
+    pub const fn to_str(self) -> Formatted<'a> {
+        Formatted::Bytes(self.0)
+    }
+}
+
+macro_rules! unsigned_args {
+    ($($t:ty),*) => {
+        $(
+            impl Arg<$t> {
+                pub const fn to_str(self) -> Formatted<'static> {
+                    format_u128(self.0 as u128, false)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! signed_args {
+    ($($t:ty),*) => {
+        $(
+            impl Arg<$t> {
+                pub const fn to_str(self) -> Formatted<'static> {
+                    format_u128((self.0 as i128).unsigned_abs(), self.0 < 0)
+                }
+            }
+        )*
+    };
+}
+
+unsigned_args!(u8, u16, u32, u64, u128, usize);
+signed_args!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! float_args {
+    ($($t:ty => $decode:ident),*) => {
+        $(
+            impl Arg<$t> {
+                pub const fn to_str(self) -> Formatted<'static> {
+                    let x = crate::float::Decoded::$decode(self.0);
+                    let (out, len) = crate::float::write_shortest::<INLINE_CAP>(x);
+
+                    let mut buf = [0u8; INLINE_CAP];
+                    let start = INLINE_CAP - len;
+                    let mut i = 0;
+                    while i < len {
+                        buf[start + i] = out[i];
+                        i += 1;
+                    }
+
+                    Formatted::Inline { buf, start }
+                }
+            }
+        )*
+    };
+}
+
+float_args!(f32 => from_f32, f64 => from_f64);
+
+impl Arg<bool> {
+    pub const fn to_str(self) -> Formatted<'static> {
+        Formatted::Borrowed(if self.0 { "true" } else { "false" })
+    }
+}
+
+impl Arg<char> {
+    pub const fn to_str(self) -> Formatted<'static> {
+        let mut buf = [0u8; INLINE_CAP];
+        let start = INLINE_CAP - self.0.len_utf8();
+        self.0.encode_utf8(buf.split_at_mut(start).1);
+
+        Formatted::Inline { buf, start }
+    }
+}
+
+enum Step {
+    Text(u8, usize),
+    Placeholder(usize, usize, usize),
+}
+
+const fn step(fmt: &[u8], i: usize) -> Step {
+    let next = if i + 1 < fmt.len() { fmt[i + 1] } else { 0 };
+
+    match fmt[i] {
+        b'{' if next == b'{' => Step::Text(b'{', i + 2),
+        b'}' if next == b'}' => Step::Text(b'}', i + 2),
+        b'}' => panic!("const_format: unmatched closing brace in format string"),
+        b'{' => {
+            let mut end = i + 1;
+            while end < fmt.len() && fmt[end] != b'}' {
+                if fmt[end] == b'{' {
+                    break;
+                }
+                end += 1;
+            }
+            if end == fmt.len() || fmt[end] != b'}' {
+                panic!("const_format: unmatched opening brace in format string");
+            }
+
+            Step::Placeholder(i + 1, end, end + 1)
+        }
+        b => Step::Text(b, i + 1),
+    }
+}
+
+/// Resolves the placeholder `fmt[start..end]` to an argument index, given the index that an
+/// empty placeholder refers to.
+const fn resolve(fmt: &[u8], start: usize, end: usize, implicit: usize, names: &[&str]) -> usize {
+    let index = if start == end {
+        implicit
+    } else if fmt[start].is_ascii_digit() {
+        let mut index = 0;
+        let mut i = start;
+        while i < end {
+            if !fmt[i].is_ascii_digit() {
+                panic!("const_format: format specs are not supported");
+            }
+            index = index * 10 + (fmt[i] - b'0') as usize;
+            i += 1;
+        }
+        index
+    } else {
+        let mut n = 0;
+        while n < names.len() && !eq_bytes(names[n].as_bytes(), fmt, start, end) {
+            n += 1;
+        }
+        if n == names.len() {
+            let mut i = start;
+            while i < end {
+                if fmt[i] == b':' {
+                    panic!("const_format: format specs are not supported");
+                }
+                i += 1;
+            }
+            panic!("const_format: format string names an argument that wasn't given");
+        }
+        n
+    };
+
+    if index >= names.len() {
+        panic!("const_format: format string references more arguments than were given");
+    }
+
+    index
+}
+
+const fn eq_bytes(name: &[u8], fmt: &[u8], start: usize, end: usize) -> bool {
+    if name.len() != end - start {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < name.len() {
+        if name[i] != fmt[start + i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+/// Formats `args` into `fmt`, returning the output and its length.
+///
+/// `names[i]` is the name that `args[i]` can be referred to by, or the empty string. Only the
+/// first `N` bytes of the output are written, so this can be called with `N = 0` to find the
+/// length to call it with. Byte string arguments are copied as they are, so the output isn't
+/// necessarily UTF-8.
+pub const fn format<const N: usize>(
+    fmt: &str,
+    names: &[&str],
+    args: &[Formatted],
+) -> ([u8; N], usize) {
+    let fmt = fmt.as_bytes();
+    let mut out = [0u8; N];
+    let mut len = 0;
+
+    let mut i = 0;
+    let mut implicit = 0;
+    while i < fmt.len() {
+        match step(fmt, i) {
+            Step::Text(b, next) => {
+                if len < N {
+                    out[len] = b;
+                }
+                len += 1;
+                i = next;
+            }
+            Step::Placeholder(start, end, next) => {
+                let arg = args[resolve(fmt, start, end, implicit, names)].as_bytes();
+                let mut j = 0;
+                while j < arg.len() {
+                    if len < N {
+                        out[len] = arg[j];
+                    }
+                    len += 1;
+                    j += 1;
+                }
+
+                if start == end {
+                    implicit += 1;
+                }
+                i = next;
+            }
+        }
+    }
+
+    let mut arg = 0;
+    while arg < args.len() {
+        let mut used = false;
+        let mut i = 0;
+        let mut implicit = 0;
+        while i < fmt.len() {
+            match step(fmt, i) {
+                Step::Text(_, next) => i = next,
+                Step::Placeholder(start, end, next) => {
+                    used |= resolve(fmt, start, end, implicit, names) == arg;
+                    if start == end {
+                        implicit += 1;
+                    }
+                    i = next;
+                }
+            }
+        }
+        if !used {
+            panic!("const_format: argument never used in format string");
+        }
+        arg += 1;
+    }
+
+    (out, len)
+}
+
+/// Formats constants into a `&'static str` using a format string with `{}` placeholders.
+///
+/// Placeholders can be empty, an argument index (`{0}`) or a name. Arguments are named with
+/// `name = VALUE`, and an argument that is just an identifier can be referred to by that
+/// identifier. Arguments are converted the same way as in [`const_concat!`], and format specs
+/// such as `{:>8}` aren't supported.
+///
+/// Unlike `format!`, names in the format string aren't captured from the surrounding scope,
+/// because a `macro_rules!` macro can't look inside a string literal. Every constant that the
+/// format string names has to be listed as an argument too:
+///
+/// ```
+/// # #[macro_use] extern crate const_concat;
+/// const HOST: &str = "localhost";
+/// const PORT: u16 = 8080;
+/// const ADDR: &str = const_format!("{HOST}:{PORT}", HOST, PORT);
+/// # fn main() { assert_eq!(ADDR, "localhost:8080"); }
+/// ```
+///
+/// Leaving one out, as in `const_format!("{HOST}")`, fails to compile with "format string names
+/// an argument that wasn't given".
+#[macro_export]
+macro_rules! const_format {
+    ($fmt:expr) => {
+        $crate::const_format!(@munch $fmt; [];)
+    };
+    ($fmt:expr, $($args:tt)*) => {
+        $crate::const_format!(@munch $fmt; []; $($args)*)
+    };
+    (@munch $fmt:expr; [$($out:tt)*]; $name:ident = $value:expr, $($rest:tt)*) => {
+        $crate::const_format!(@munch $fmt; [$($out)* (stringify!($name), $value)]; $($rest)*)
+    };
+    (@munch $fmt:expr; [$($out:tt)*]; $name:ident = $value:expr) => {
+        $crate::const_format!(@munch $fmt; [$($out)* (stringify!($name), $value)];)
+    };
+    (@munch $fmt:expr; [$($out:tt)*]; $name:ident, $($rest:tt)*) => {
+        $crate::const_format!(@munch $fmt; [$($out)* (stringify!($name), $name)]; $($rest)*)
+    };
+    (@munch $fmt:expr; [$($out:tt)*]; $name:ident) => {
+        $crate::const_format!(@munch $fmt; [$($out)* (stringify!($name), $name)];)
+    };
+    (@munch $fmt:expr; [$($out:tt)*]; $value:expr, $($rest:tt)*) => {
+        $crate::const_format!(@munch $fmt; [$($out)* ("", $value)]; $($rest)*)
+    };
+    (@munch $fmt:expr; [$($out:tt)*]; $value:expr) => {
+        $crate::const_format!(@munch $fmt; [$($out)* ("", $value)];)
+    };
+    (@munch $fmt:expr; [$(($name:expr, $value:expr))*];) => {{
+        const __FMT: &str = $fmt;
+        const __NAMES: &[&str] = &[$($name),*];
+        const __ARGS: &[$crate::fmt::Formatted] = &[$($crate::fmt::Arg($value).to_str()),*];
+        const __LEN: usize = $crate::fmt::format::<0>(__FMT, __NAMES, __ARGS).1;
+        const __BYTES: [u8; __LEN] = $crate::fmt::format::<__LEN>(__FMT, __NAMES, __ARGS).0;
+        const __S: &str = $crate::output_to_str("const_format", &__BYTES);
+        __S
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Arg;
+    use crate::const_concat;
+
+    #[test]
+    fn integers() {
+        const MAJOR: u32 = 1;
+        const MINOR: u8 = 12;
+        const VERSION: &str = const_concat!("v", MAJOR, ".", MINOR);
+        const EXTREMES: &str = const_concat!(
+            i128::MIN,
+            " ",
+            u128::MAX,
+            " ",
+            i8::MIN,
+            " ",
+            0usize,
+            " ",
+            -7isize
+        );
+
+        assert_eq!(VERSION, "v1.12");
+        assert_eq!(
+            EXTREMES,
+            format!(
+                "{} {} {} {} {}",
+                i128::MIN,
+                u128::MAX,
+                i8::MIN,
+                0usize,
+                -7isize
+            )
+        );
+    }
+
+    #[test]
+    fn bools_and_chars() {
+        const ENABLED: bool = true;
+        const SEP: char = '→';
+        const TEXT: &str = const_concat!(ENABLED, SEP, false, 'x', 'é', '𝄞');
+
+        assert_eq!(TEXT, "true→falsexé𝄞");
+    }
+
+    #[test]
+    fn options() {
+        const SUFFIX: Option<&str> = Some("-beta");
+        const MISSING: Option<&str> = None;
+
+        assert_eq!(const_concat!("1.0", SUFFIX, MISSING, "+build"), "1.0-beta+build");
+    }
+
+    #[test]
+    fn single_non_string() {
+        const ANSWER: &str = const_concat!(42u64);
+
+        assert_eq!(ANSWER, "42");
+    }
+
+    #[test]
+    fn generic_constants() {
+        trait Plugin {
+            const PREFIX: &'static str;
+            const NAME: &'static str;
+            const ID: &'static str = const_concat!(capacity = 64; Self::PREFIX, "::", Self::NAME);
+        }
+
+        trait Describe {
+            const DESCRIPTION: &'static str;
+        }
+
+        impl<T: Plugin> Describe for T {
+            const DESCRIPTION: &'static str =
+                const_concat!(capacity = 64; T::ID, " (", T::NAME.len(), " chars)",);
+        }
+
+        struct Audio;
+
+        impl Plugin for Audio {
+            const PREFIX: &'static str = "media";
+            const NAME: &'static str = "audio";
+        }
+
+        fn id<T: Plugin>() -> &'static str {
+            const_concat!(capacity = 64; T::PREFIX, "/", T::NAME)
+        }
+
+        let local = const_concat!(capacity = 8; "a", 1u8);
+
+        assert_eq!(Audio::ID, "media::audio");
+        assert_eq!(Audio::DESCRIPTION, "media::audio (5 chars)");
+        assert_eq!(id::<Audio>(), "media/audio");
+        assert_eq!(local, "a1");
+    }
+
+    #[test]
+    #[should_panic(expected = "const_concat: output is longer than the given capacity")]
+    fn capacity_exceeded() {
+        super::concat_with_capacity::<4>(&[Arg("ab").to_str(), Arg("cde").to_str()]);
+    }
+
+    #[test]
+    fn capacity_byte_strings() {
+        const SPLIT: &str = const_concat!(capacity = 8; "caf", b"\xc3", b"\xa9");
+
+        assert_eq!(SPLIT, "café");
+    }
+
+    #[test]
+    #[should_panic(expected = "const_concat: invalid UTF-8 at byte offset 3")]
+    fn capacity_invalid_utf8() {
+        super::concat_with_capacity::<8>(&[Arg("caf").to_str(), Arg(b"\xc3").to_str()]);
+    }
+
+    #[test]
+    fn format_placeholders() {
+        const HOST: &str = "example.com";
+        const PORT: u16 = 8080;
+        const PATH: &str = "api";
+        const URL: &str = const_format!("https://{}:{}/{}?x={}", HOST, PORT, PATH, true);
+        const NAMED: &str = const_format!("{HOST}:{PORT}/{path}{{{0}}}", HOST, PORT, path = PATH);
+        const PLAIN: &str = const_format!("no {{placeholders}}");
+
+        assert_eq!(URL, "https://example.com:8080/api?x=true");
+        assert_eq!(NAMED, "example.com:8080/api{example.com}");
+        assert_eq!(PLAIN, "no {placeholders}");
+    }
+
+    #[test]
+    #[should_panic(expected = "references more arguments than were given")]
+    fn format_too_few_arguments() {
+        super::format::<0>("{}/{}", &[""], &[Arg("a").to_str()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "argument never used")]
+    fn format_too_many_arguments() {
+        super::format::<0>("{}", &["", ""], &[Arg("a").to_str(), Arg("b").to_str()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "names an argument that wasn't given")]
+    fn format_unknown_name() {
+        super::format::<0>("{missing}", &["present"], &[Arg("a").to_str()]);
+    }
+
+    #[test]
+    fn format_byte_strings() {
+        const SPLIT: &str = const_format!("caf{}{} {}", b"\xc3", b"\xa9", b"ok");
+
+        assert_eq!(SPLIT, "café ok");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid UTF-8 in byte string argument at byte offset 1")]
+    fn invalid_byte_string_argument() {
+        Arg(b"a\xff").to_str().as_str();
+    }
+}
@@ -0,0 +1,466 @@
+//! Hash functions that can be evaluated in constants.
+//!
+//! These are ordinary `const fn`s, so calling one at runtime gives the same result as
+//! [`const_hash!`] gives at compile time.
+
+/// 32-bit FNV-1a.
+pub const fn fnv1a_32(bytes: &[u8]) -> u32 {
+    let mut hash = 0x811c_9dc5u32;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+
+    hash
+}
+
+/// 64-bit FNV-1a.
+pub const fn fnv1a_64(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+
+    hash
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 as used by zlib, gzip and PNG (the reflected IEEE polynomial).
+pub const fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    let mut i = 0;
+    while i < bytes.len() {
+        crc = CRC32_TABLE[((crc ^ bytes[i] as u32) & 0xff) as usize] ^ (crc >> 8);
+        i += 1;
+    }
+
+    !crc
+}
+
+const XXH32_PRIME_1: u32 = 0x9e37_79b1;
+const XXH32_PRIME_2: u32 = 0x85eb_ca77;
+const XXH32_PRIME_3: u32 = 0xc2b2_ae3d;
+const XXH32_PRIME_4: u32 = 0x27d4_eb2f;
+const XXH32_PRIME_5: u32 = 0x1656_67b1;
+
+/// 32-bit xxHash (XXH32) with the given seed.
+pub const fn xxh32(bytes: &[u8], seed: u32) -> u32 {
+    const fn round(acc: u32, input: u32) -> u32 {
+        acc.wrapping_add(input.wrapping_mul(XXH32_PRIME_2))
+            .rotate_left(13)
+            .wrapping_mul(XXH32_PRIME_1)
+    }
+
+    let len = bytes.len();
+    let mut i = 0;
+    let mut hash = if len >= 16 {
+        let mut v1 = seed.wrapping_add(XXH32_PRIME_1).wrapping_add(XXH32_PRIME_2);
+        let mut v2 = seed.wrapping_add(XXH32_PRIME_2);
+        let mut v3 = seed;
+        let mut v4 = seed.wrapping_sub(XXH32_PRIME_1);
+        while i + 16 <= len {
+            v1 = round(v1, read_u32(bytes, i));
+            v2 = round(v2, read_u32(bytes, i + 4));
+            v3 = round(v3, read_u32(bytes, i + 8));
+            v4 = round(v4, read_u32(bytes, i + 12));
+            i += 16;
+        }
+        v1.rotate_left(1)
+            .wrapping_add(v2.rotate_left(7))
+            .wrapping_add(v3.rotate_left(12))
+            .wrapping_add(v4.rotate_left(18))
+    } else {
+        seed.wrapping_add(XXH32_PRIME_5)
+    };
+
+    hash = hash.wrapping_add(len as u32);
+    while i + 4 <= len {
+        hash = hash.wrapping_add(read_u32(bytes, i).wrapping_mul(XXH32_PRIME_3));
+        hash = hash.rotate_left(17).wrapping_mul(XXH32_PRIME_4);
+        i += 4;
+    }
+    while i < len {
+        hash = hash.wrapping_add((bytes[i] as u32).wrapping_mul(XXH32_PRIME_5));
+        hash = hash.rotate_left(11).wrapping_mul(XXH32_PRIME_1);
+        i += 1;
+    }
+
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(XXH32_PRIME_2);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(XXH32_PRIME_3);
+    hash ^ (hash >> 16)
+}
+
+const XXH64_PRIME_1: u64 = 0x9e37_79b1_85eb_ca87;
+const XXH64_PRIME_2: u64 = 0xc2b2_ae3d_27d4_eb4f;
+const XXH64_PRIME_3: u64 = 0x1656_67b1_9e37_79f9;
+const XXH64_PRIME_4: u64 = 0x85eb_ca77_c2b2_ae63;
+const XXH64_PRIME_5: u64 = 0x27d4_eb2f_1656_67c5;
+
+/// 64-bit xxHash (XXH64) with the given seed.
+pub const fn xxh64(bytes: &[u8], seed: u64) -> u64 {
+    const fn round(acc: u64, input: u64) -> u64 {
+        acc.wrapping_add(input.wrapping_mul(XXH64_PRIME_2))
+            .rotate_left(31)
+            .wrapping_mul(XXH64_PRIME_1)
+    }
+
+    const fn merge(acc: u64, value: u64) -> u64 {
+        (acc ^ round(0, value)).wrapping_mul(XXH64_PRIME_1).wrapping_add(XXH64_PRIME_4)
+    }
+
+    let len = bytes.len();
+    let mut i = 0;
+    let mut hash = if len >= 32 {
+        let mut v1 = seed.wrapping_add(XXH64_PRIME_1).wrapping_add(XXH64_PRIME_2);
+        let mut v2 = seed.wrapping_add(XXH64_PRIME_2);
+        let mut v3 = seed;
+        let mut v4 = seed.wrapping_sub(XXH64_PRIME_1);
+        while i + 32 <= len {
+            v1 = round(v1, read_u64(bytes, i));
+            v2 = round(v2, read_u64(bytes, i + 8));
+            v3 = round(v3, read_u64(bytes, i + 16));
+            v4 = round(v4, read_u64(bytes, i + 24));
+            i += 32;
+        }
+        let hash = v1
+            .rotate_left(1)
+            .wrapping_add(v2.rotate_left(7))
+            .wrapping_add(v3.rotate_left(12))
+            .wrapping_add(v4.rotate_left(18));
+        merge(merge(merge(merge(hash, v1), v2), v3), v4)
+    } else {
+        seed.wrapping_add(XXH64_PRIME_5)
+    };
+
+    hash = hash.wrapping_add(len as u64);
+    while i + 8 <= len {
+        hash ^= round(0, read_u64(bytes, i));
+        hash = hash.rotate_left(27).wrapping_mul(XXH64_PRIME_1).wrapping_add(XXH64_PRIME_4);
+        i += 8;
+    }
+    if i + 4 <= len {
+        hash ^= (read_u32(bytes, i) as u64).wrapping_mul(XXH64_PRIME_1);
+        hash = hash.rotate_left(23).wrapping_mul(XXH64_PRIME_2).wrapping_add(XXH64_PRIME_3);
+        i += 4;
+    }
+    while i < len {
+        hash ^= (bytes[i] as u64).wrapping_mul(XXH64_PRIME_5);
+        hash = hash.rotate_left(11).wrapping_mul(XXH64_PRIME_1);
+        i += 1;
+    }
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(XXH64_PRIME_2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(XXH64_PRIME_3);
+    hash ^ (hash >> 32)
+}
+
+const fn read_u32(bytes: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]])
+}
+
+const fn read_u64(bytes: &[u8], i: usize) -> u64 {
+    read_u32(bytes, i) as u64 | (read_u32(bytes, i + 4) as u64) << 32
+}
+
+/// Hashes a constant at compile time with one of the functions in [`hash`](crate::hash).
+///
+/// The input takes the same arguments as [`const_concat_bytes!`], so strings are hashed as UTF-8.
+/// The xxHash functions take an optional seed, which defaults to 0:
+/// `const_hash!(xxh64, NAME, 42)`.
+#[macro_export]
+macro_rules! const_hash {
+    (@output fnv1a_32) => { u32 };
+    (@output fnv1a_64) => { u64 };
+    (@output crc32) => { u32 };
+    (@output xxh32) => { u32 };
+    (@output xxh64) => { u64 };
+    (xxh32, $input:expr) => {
+        $crate::const_hash!(xxh32, $input, 0)
+    };
+    (xxh64, $input:expr) => {
+        $crate::const_hash!(xxh64, $input, 0)
+    };
+    ($algorithm:ident, $input:expr, $seed:expr) => {{
+        const __HASH: $crate::const_hash!(@output $algorithm) =
+            $crate::hash::$algorithm($crate::bytes::ByteArg($input).as_bytes(), $seed);
+        __HASH
+    }};
+    ($algorithm:ident, $input:expr) => {{
+        const __HASH: $crate::const_hash!(@output $algorithm) =
+            $crate::hash::$algorithm($crate::bytes::ByteArg($input).as_bytes());
+        __HASH
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::const_concat;
+
+    const LONG: &str = "Nobody inspects the spammish repetition";
+
+    #[test]
+    fn known_values() {
+        assert_eq!(const_hash!(fnv1a_32, ""), 0x811c_9dc5);
+        assert_eq!(const_hash!(fnv1a_32, "foobar"), 0xbf9c_f968);
+        assert_eq!(const_hash!(fnv1a_64, ""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(const_hash!(fnv1a_64, "foobar"), 0x8594_4171_f739_67e8);
+        assert_eq!(const_hash!(crc32, ""), 0);
+        assert_eq!(const_hash!(crc32, "123456789"), 0xcbf4_3926);
+        assert_eq!(const_hash!(xxh32, ""), 0x02cc_5d05);
+        assert_eq!(const_hash!(xxh32, "abc"), 0x32d1_53ff);
+        assert_eq!(const_hash!(xxh32, LONG), 0xe229_3b2f);
+        assert_eq!(const_hash!(xxh64, ""), 0xef46_db37_51d8_e999);
+        assert_eq!(const_hash!(xxh64, "abc"), 0x44bc_2cf5_ad77_0999);
+        assert_eq!(const_hash!(xxh64, LONG), 0xfbce_a83c_8a37_8bf1);
+    }
+
+    /// Straightforward runtime versions of each hash, written from the specifications rather
+    /// than sharing code with the `const fn`s.
+    mod reference {
+        use std::convert::TryInto;
+
+        pub fn fnv1a_32(bytes: &[u8]) -> u32 {
+            bytes.iter().fold(0x811c_9dc5, |hash, &b| (hash ^ b as u32).wrapping_mul(0x0100_0193))
+        }
+
+        pub fn fnv1a_64(bytes: &[u8]) -> u64 {
+            bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+                (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+        }
+
+        pub fn crc32(bytes: &[u8]) -> u32 {
+            let mut crc = !0u32;
+            for &b in bytes {
+                crc ^= b as u32;
+                for _ in 0..8 {
+                    crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+                }
+            }
+            !crc
+        }
+
+        pub fn xxh32(bytes: &[u8], seed: u32) -> u32 {
+            const P: [u32; 5] = [0x9e37_79b1, 0x85eb_ca77, 0xc2b2_ae3d, 0x27d4_eb2f, 0x1656_67b1];
+            let round = |acc: u32, lane: u32| {
+                acc.wrapping_add(lane.wrapping_mul(P[1])).rotate_left(13).wrapping_mul(P[0])
+            };
+            let lane = |chunk: &[u8]| u32::from_le_bytes(chunk.try_into().unwrap());
+
+            let stripes = bytes.chunks_exact(16);
+            let mut rest = stripes.remainder();
+            let mut hash = if bytes.len() >= 16 {
+                let mut acc = [
+                    seed.wrapping_add(P[0]).wrapping_add(P[1]),
+                    seed.wrapping_add(P[1]),
+                    seed,
+                    seed.wrapping_sub(P[0]),
+                ];
+                for stripe in stripes {
+                    for (acc, chunk) in acc.iter_mut().zip(stripe.chunks_exact(4)) {
+                        *acc = round(*acc, lane(chunk));
+                    }
+                }
+                [1, 7, 12, 18]
+                    .iter()
+                    .zip(acc)
+                    .fold(0u32, |hash, (&r, acc)| hash.wrapping_add(acc.rotate_left(r)))
+            } else {
+                seed.wrapping_add(P[4])
+            };
+
+            hash = hash.wrapping_add(bytes.len() as u32);
+            while rest.len() >= 4 {
+                hash = hash.wrapping_add(lane(&rest[..4]).wrapping_mul(P[2]));
+                hash = hash.rotate_left(17).wrapping_mul(P[3]);
+                rest = &rest[4..];
+            }
+            for &b in rest {
+                hash = hash.wrapping_add((b as u32).wrapping_mul(P[4]));
+                hash = hash.rotate_left(11).wrapping_mul(P[0]);
+            }
+
+            hash = (hash ^ (hash >> 15)).wrapping_mul(P[1]);
+            hash = (hash ^ (hash >> 13)).wrapping_mul(P[2]);
+            hash ^ (hash >> 16)
+        }
+
+        pub fn xxh64(bytes: &[u8], seed: u64) -> u64 {
+            const P: [u64; 5] = [
+                0x9e37_79b1_85eb_ca87,
+                0xc2b2_ae3d_27d4_eb4f,
+                0x1656_67b1_9e37_79f9,
+                0x85eb_ca77_c2b2_ae63,
+                0x27d4_eb2f_1656_67c5,
+            ];
+            let round = |acc: u64, lane: u64| {
+                acc.wrapping_add(lane.wrapping_mul(P[1])).rotate_left(31).wrapping_mul(P[0])
+            };
+            let lane = |chunk: &[u8]| u64::from_le_bytes(chunk.try_into().unwrap());
+
+            let stripes = bytes.chunks_exact(32);
+            let mut rest = stripes.remainder();
+            let mut hash = if bytes.len() >= 32 {
+                let mut acc = [
+                    seed.wrapping_add(P[0]).wrapping_add(P[1]),
+                    seed.wrapping_add(P[1]),
+                    seed,
+                    seed.wrapping_sub(P[0]),
+                ];
+                for stripe in stripes {
+                    for (acc, chunk) in acc.iter_mut().zip(stripe.chunks_exact(8)) {
+                        *acc = round(*acc, lane(chunk));
+                    }
+                }
+                let hash = [1, 7, 12, 18]
+                    .iter()
+                    .zip(acc)
+                    .fold(0u64, |hash, (&r, acc)| hash.wrapping_add(acc.rotate_left(r)));
+                acc.iter().fold(hash, |hash, &acc| {
+                    (hash ^ round(0, acc)).wrapping_mul(P[0]).wrapping_add(P[3])
+                })
+            } else {
+                seed.wrapping_add(P[4])
+            };
+
+            hash = hash.wrapping_add(bytes.len() as u64);
+            while rest.len() >= 8 {
+                hash ^= round(0, lane(&rest[..8]));
+                hash = hash.rotate_left(27).wrapping_mul(P[0]).wrapping_add(P[3]);
+                rest = &rest[8..];
+            }
+            if rest.len() >= 4 {
+                let word = u32::from_le_bytes(rest[..4].try_into().unwrap());
+                hash ^= (word as u64).wrapping_mul(P[0]);
+                hash = hash.rotate_left(23).wrapping_mul(P[1]).wrapping_add(P[2]);
+                rest = &rest[4..];
+            }
+            for &b in rest {
+                hash ^= (b as u64).wrapping_mul(P[4]);
+                hash = hash.rotate_left(11).wrapping_mul(P[0]);
+            }
+
+            hash = (hash ^ (hash >> 33)).wrapping_mul(P[1]);
+            hash = (hash ^ (hash >> 29)).wrapping_mul(P[2]);
+            hash ^ (hash >> 32)
+        }
+    }
+
+    #[test]
+    fn compile_time_matches_runtime() {
+        const MODULE: &str = "registry";
+        const NAME: &str = const_concat!(MODULE, "::", "handler");
+        const FNV32: u32 = const_hash!(fnv1a_32, NAME);
+        const FNV64: u64 = const_hash!(fnv1a_64, NAME);
+        const CRC: u32 = const_hash!(crc32, NAME);
+        const XXH32: u32 = const_hash!(xxh32, NAME, 7);
+        const XXH64: u64 = const_hash!(xxh64, NAME, 7);
+
+        let name = format!("{}::{}", MODULE, "handler");
+        assert_eq!(FNV32, fnv1a_32(name.as_bytes()));
+        assert_eq!(FNV64, fnv1a_64(name.as_bytes()));
+        assert_eq!(CRC, crc32(name.as_bytes()));
+        assert_eq!(XXH32, xxh32(name.as_bytes(), 7));
+        assert_eq!(XXH64, xxh64(name.as_bytes(), 7));
+        assert_eq!(FNV32, reference::fnv1a_32(name.as_bytes()));
+        assert_eq!(FNV64, reference::fnv1a_64(name.as_bytes()));
+        assert_eq!(CRC, reference::crc32(name.as_bytes()));
+        assert_eq!(XXH32, reference::xxh32(name.as_bytes(), 7));
+        assert_eq!(XXH64, reference::xxh64(name.as_bytes(), 7));
+
+        // Every length up to a few xxHash stripes, to cover each tail case.
+        const BYTES: [u8; 100] = {
+            let mut bytes = [0; 100];
+            let mut i = 0;
+            while i < 100 {
+                bytes[i] = (i as u8).wrapping_mul(37) ^ 0xa5;
+                i += 1;
+            }
+            bytes
+        };
+        const FNV32_ALL: [u32; 101] = {
+            let mut hashes = [0; 101];
+            let mut i = 0;
+            while i <= 100 {
+                hashes[i] = fnv1a_32(BYTES.split_at(i).0);
+                i += 1;
+            }
+            hashes
+        };
+        const FNV64_ALL: [u64; 101] = {
+            let mut hashes = [0; 101];
+            let mut i = 0;
+            while i <= 100 {
+                hashes[i] = fnv1a_64(BYTES.split_at(i).0);
+                i += 1;
+            }
+            hashes
+        };
+        const CRC_ALL: [u32; 101] = {
+            let mut hashes = [0; 101];
+            let mut i = 0;
+            while i <= 100 {
+                hashes[i] = crc32(BYTES.split_at(i).0);
+                i += 1;
+            }
+            hashes
+        };
+        const XXH32_ALL: [u32; 101] = {
+            let mut hashes = [0; 101];
+            let mut i = 0;
+            while i <= 100 {
+                hashes[i] = xxh32(BYTES.split_at(i).0, 1);
+                i += 1;
+            }
+            hashes
+        };
+        const XXH64_ALL: [u64; 101] = {
+            let mut hashes = [0; 101];
+            let mut i = 0;
+            while i <= 100 {
+                hashes[i] = xxh64(BYTES.split_at(i).0, 1);
+                i += 1;
+            }
+            hashes
+        };
+        for i in 0..=100 {
+            let bytes = BYTES[..i].to_vec();
+            assert_eq!(FNV32_ALL[i], fnv1a_32(&bytes), "length {}", i);
+            assert_eq!(FNV64_ALL[i], fnv1a_64(&bytes), "length {}", i);
+            assert_eq!(CRC_ALL[i], crc32(&bytes), "length {}", i);
+            assert_eq!(XXH32_ALL[i], xxh32(&bytes, 1), "length {}", i);
+            assert_eq!(XXH64_ALL[i], xxh64(&bytes, 1), "length {}", i);
+
+            assert_eq!(FNV32_ALL[i], reference::fnv1a_32(&bytes), "length {}", i);
+            assert_eq!(FNV64_ALL[i], reference::fnv1a_64(&bytes), "length {}", i);
+            assert_eq!(CRC_ALL[i], reference::crc32(&bytes), "length {}", i);
+            assert_eq!(XXH32_ALL[i], reference::xxh32(&bytes, 1), "length {}", i);
+            assert_eq!(XXH64_ALL[i], reference::xxh64(&bytes, 1), "length {}", i);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Hex encoding and decoding in constants.
+
+const LOWER: &[u8; 16] = b"0123456789abcdef";
+const UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Hex-encodes `bytes` into a `[u8; N]`, with uppercase digits if `upper` is `true`.
+///
+/// Panics if `N` isn't `2 * bytes.len()`.
+pub const fn encode<const N: usize>(bytes: &[u8], upper: bool) -> [u8; N] {
+    if bytes.len() * 2 != N {
+        panic!("const_hex_encode: output length `N` is not twice the input length");
+    }
+
+    let digits = if upper { UPPER } else { LOWER };
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < bytes.len() {
+        out[2 * i] = digits[(bytes[i] >> 4) as usize];
+        out[2 * i + 1] = digits[(bytes[i] & 0xf) as usize];
+        i += 1;
+    }
+
+    out
+}
+
+/// Decodes the hex string `s` into a `[u8; N]`, accepting upper and lowercase digits.
+///
+/// Panics if `s` has an odd length or anything other than hex digits, or if `N` isn't half its
+/// length.
+pub const fn decode<const N: usize>(s: &str) -> [u8; N] {
+    let s = s.as_bytes();
+    if s.len() % 2 != 0 {
+        panic!("const_hex_decode: odd number of hex digits");
+    }
+    if s.len() / 2 != N {
+        panic!("const_hex_decode: output length `N` is not half the input length");
+    }
+
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = (digit(s[2 * i]) << 4) | digit(s[2 * i + 1]);
+        i += 1;
+    }
+
+    out
+}
+
+const fn digit(b: u8) -> u8 {
+    match b {
+        b'0'..=b'9' => b - b'0',
+        b'a'..=b'f' => b - b'a' + 10,
+        b'A'..=b'F' => b - b'A' + 10,
+        _ => panic!("const_hex_decode: invalid hex digit"),
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_hex_encode {
+    ($bytes:expr, $upper:expr) => {{
+        const __BYTES: &[u8] = $crate::bytes::ByteArg($bytes).as_bytes();
+        const __OUT: [u8; __BYTES.len() * 2] =
+            $crate::hex::encode::<{ __BYTES.len() * 2 }>(__BYTES, $upper);
+
+        unsafe { $crate::from_utf8_unchecked(&__OUT) }
+    }};
+}
+
+/// Hex-encodes a constant with lowercase digits, into a `&'static str`.
+///
+/// Takes the same arguments as [`const_concat_bytes!`], so strings are encoded as UTF-8.
+#[macro_export]
+macro_rules! const_hex_encode {
+    ($bytes:expr) => {
+        $crate::__const_hex_encode!($bytes, false)
+    };
+}
+
+/// Hex-encodes a constant with uppercase digits, into a `&'static str`.
+#[macro_export]
+macro_rules! const_hex_encode_upper {
+    ($bytes:expr) => {
+        $crate::__const_hex_encode!($bytes, true)
+    };
+}
+
+/// Decodes a constant hex string into a `[u8; N]`, failing to compile if it isn't valid hex.
+#[macro_export]
+macro_rules! const_hex_decode {
+    ($s:expr) => {{
+        const __S: &str = $s;
+        const __OUT: [u8; __S.len() / 2] = $crate::hex::decode::<{ __S.len() / 2 }>(__S);
+        __OUT
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+    use crate::{const_concat, const_concat_bytes};
+
+    #[test]
+    fn encode() {
+        const KEY: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+        const FINGERPRINT: &str = const_concat!("SHA1:", const_hex_encode_upper!(KEY));
+
+        assert_eq!(const_hex_encode!(KEY), "deadbeef");
+        assert_eq!(FINGERPRINT, "SHA1:DEADBEEF");
+        assert_eq!(const_hex_encode!("Az\n"), "417a0a");
+        assert_eq!(const_hex_encode!(const_concat_bytes!(0u8, 255u8)), "00ff");
+        assert_eq!(const_hex_encode!(b""), "");
+    }
+
+    #[test]
+    fn decode_round_trip() {
+        const KEY: [u8; 4] = const_hex_decode!("DeadBEEF");
+        const ALL: [u8; 256] = {
+            let mut all = [0u8; 256];
+            let mut i = 0;
+            while i < 256 {
+                all[i] = i as u8;
+                i += 1;
+            }
+            all
+        };
+        const ROUND_TRIP: [u8; 256] = const_hex_decode!(const_hex_encode!(ALL));
+
+        assert_eq!(KEY, [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(ROUND_TRIP, ALL);
+        assert_eq!(const_hex_decode!(""), []);
+    }
+
+    #[test]
+    #[should_panic(expected = "odd number of hex digits")]
+    fn decode_odd_length() {
+        decode::<1>("abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid hex digit")]
+    fn decode_invalid_digit() {
+        decode::<2>("0g12");
+    }
+}
@@ -0,0 +1,189 @@
+use crate::fmt::{Arg, Formatted};
+
+/// Wrapper used by [`const_join!`] to accept a single argument, which is either a constant list
+/// of strings or anything that [`Arg`](crate::fmt::Arg) accepts.
+///
+/// Works like [`ByteArg`](crate::bytes::ByteArg). `to_array` turns a list into its strings, and
+/// any other argument into a list of just that argument, formatted.
+pub struct Parts<T>(pub T);
+
+macro_rules! list_parts {
+    ($([$($generics:tt)*] $t:ty),*) => {
+        $(
+            impl<$($generics)*> Parts<$t> {
+                pub const fn count(&self) -> usize {
+                    self.0.len()
+                }
+
+                pub const fn to_array<const LEN: usize>(&self) -> [Formatted<'a>; LEN] {
+                    let mut parts = [Formatted::Borrowed(""); LEN];
+                    let mut i = 0;
+                    while i < LEN {
+                        parts[i] = Formatted::Borrowed(self.0[i]);
+                        i += 1;
+                    }
+                    parts
+                }
+            }
+        )*
+    };
+}
+
+list_parts!(
+    ['a] &'a [&'a str],
+    ['a, const N: usize] &'a [&'a str; N],
+    ['a, const N: usize] [&'a str; N]
+);
+
+macro_rules! single_parts {
+    ($([$($generics:tt)*] $t:ty),*) => {
+        $(
+            impl<$($generics)*> Parts<$t> {
+                pub const fn count(&self) -> usize {
+                    1
+                }
+
+                pub const fn to_array<const LEN: usize>(&self) -> [Formatted<'a>; LEN] {
+                    [Arg(self.0).to_str(); LEN]
+                }
+            }
+        )*
+    };
+}
+
+single_parts!(
+    ['a] &'a str,
+    ['a] Option<&'a str>,
+    ['a] &'a [u8],
+    ['a, const N: usize] &'a [u8; N],
+    ['a] u8, ['a] u16, ['a] u32, ['a] u64, ['a] u128, ['a] usize,
+    ['a] i8, ['a] i16, ['a] i32, ['a] i64, ['a] i128, ['a] isize,
+    ['a] f32, ['a] f64, ['a] bool, ['a] char
+);
+
+/// Joins `parts` with `sep` in between, returning the output and its length.
+///
+/// Like [`format`](crate::fmt::format), only the first `N` bytes are written, so this can be
+/// called with `N = 0` to find the length to call it with, and byte string parts are copied as
+/// they are.
+pub const fn join<const N: usize>(sep: &[u8], parts: &[Formatted]) -> ([u8; N], usize) {
+    let mut out = [0u8; N];
+    let mut len = 0;
+
+    let mut i = 0;
+    while i < parts.len() {
+        if i > 0 {
+            let mut j = 0;
+            while j < sep.len() {
+                if len < N {
+                    out[len] = sep[j];
+                }
+                len += 1;
+                j += 1;
+            }
+        }
+
+        let part = parts[i].as_bytes();
+        let mut j = 0;
+        while j < part.len() {
+            if len < N {
+                out[len] = part[j];
+            }
+            len += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+
+    (out, len)
+}
+
+/// Joins constants into a `&'static str` with a separator between them.
+///
+/// Takes either several arguments, converted the same way as in [`const_concat!`], or a single
+/// constant list of strings: `const_join!(", "; A, B, C)` or `const_join!(", "; LIST)`. A single
+/// argument that isn't a list is converted like the others, so `const_join!(", "; PORT)` is just
+/// `PORT` formatted. Like with [`const_concat!`], it's the joined output that has to be valid
+/// UTF-8 when some of the arguments are byte strings.
+#[macro_export]
+macro_rules! const_join {
+    ($sep:expr; $list:expr) => {{
+        const __COUNT: usize = $crate::join::Parts($list).count();
+        const __PARTS: [$crate::fmt::Formatted; __COUNT] =
+            $crate::join::Parts($list).to_array::<__COUNT>();
+        $crate::const_join!(@join $sep, &__PARTS)
+    }};
+    ($sep:expr; $($arg:expr),+) => {{
+        const __PARTS: &[$crate::fmt::Formatted] = &[$($crate::fmt::Arg($arg).to_str()),+];
+        $crate::const_join!(@join $sep, __PARTS)
+    }};
+    ($sep:expr; $($arg:expr),+,) => {
+        $crate::const_join!($sep; $($arg),+)
+    };
+    (@join $sep:expr, $parts:expr) => {{
+        const __SEP: &[u8] = $crate::fmt::Arg($sep).to_str().as_bytes();
+        const __LEN: usize = $crate::join::join::<0>(__SEP, $parts).1;
+        const __BYTES: [u8; __LEN] = $crate::join::join::<__LEN>(__SEP, $parts).0;
+        const __S: &str = $crate::output_to_str("const_join", &__BYTES);
+        __S
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fmt::Arg;
+
+    #[test]
+    fn join_arguments() {
+        const A: &str = "alpha";
+        const B: &str = "beta";
+        const PORT: u16 = 80;
+        const JOINED: &str = const_join!(", "; A, B, PORT);
+        const TRAILING_COMMA: &str = const_join!('/'; A, B,);
+
+        assert_eq!(JOINED, "alpha, beta, 80");
+        assert_eq!(TRAILING_COMMA, "alpha/beta");
+    }
+
+    #[test]
+    fn join_list() {
+        const LIST: &[&str] = &["x", "y", "z"];
+        const ARRAY: [&str; 2] = ["left", "right"];
+        const SINGLE: &str = "only";
+        const EMPTY: &[&str] = &[];
+
+        assert_eq!(const_join!(" | "; LIST), "x | y | z");
+        assert_eq!(const_join!(" | "; ARRAY), "left | right");
+        assert_eq!(const_join!(" | "; SINGLE), "only");
+        assert_eq!(const_join!(" | "; EMPTY), "");
+    }
+
+    #[test]
+    fn join_single_argument() {
+        const PORT: u16 = 8080;
+        const SUFFIX: Option<&str> = Some("beta");
+
+        assert_eq!(const_join!(", "; PORT), "8080");
+        assert_eq!(const_join!(", "; 'x'), "x");
+        assert_eq!(const_join!(", "; true), "true");
+        assert_eq!(const_join!(", "; SUFFIX), "beta");
+        assert_eq!(const_join!(", "; b"bytes"), "bytes");
+        assert_eq!(const_join!(", "; 1.5f64), "1.5");
+    }
+
+    #[test]
+    fn join_byte_strings() {
+        const SPLIT: &str = const_join!(b"\xc3"; "caf", b"\xa9");
+
+        assert_eq!(SPLIT, "café");
+        assert_eq!(const_join!(", "; b"ok"), "ok");
+    }
+
+    #[test]
+    #[should_panic(expected = "const_join: invalid UTF-8 at byte offset 2")]
+    fn invalid_utf8() {
+        let parts = [Arg("a").to_str(), Arg(b"\xc3").to_str()];
+        let (out, _) = super::join::<3>(b",", &parts);
+        crate::output_to_str("const_join", &out);
+    }
+}
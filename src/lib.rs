@@ -1,10 +1,33 @@
-#![feature(
-    const_fn,
-    const_fn_union,
-    untagged_unions,
-    const_raw_ptr_deref
-)]
+pub mod base64;
+#[doc(hidden)]
+pub mod bytes;
+pub mod case;
+mod const_str;
+pub mod float;
+#[doc(hidden)]
+pub mod fmt;
+pub mod hash;
+pub mod hex;
+#[doc(hidden)]
+pub mod join;
+pub mod pad;
+pub mod path;
+pub mod query;
+pub mod replace;
+#[doc(hidden)]
+pub mod slices;
+pub mod substr;
+pub mod utf16;
+mod utf8;
 
+pub use const_str::ConstStr;
+
+/// Reinterprets `from` as a `To`, usable in constant expressions.
+///
+/// # Safety
+///
+/// Same requirements as `std::mem::transmute`, except that the sizes of `From` and `To` are not
+/// checked.
 pub const unsafe fn transmute<From, To>(from: From) -> To {
     union Transmute<From, To> {
         from: std::mem::ManuallyDrop<From>,
@@ -14,52 +37,275 @@ pub const unsafe fn transmute<From, To>(from: From) -> To {
     std::mem::ManuallyDrop::into_inner(Transmute { from: std::mem::ManuallyDrop::new(from) }.to)
 }
 
-pub const unsafe fn concat<First, Second, Out>(a: &[u8], b: &[u8]) -> Out
-where
-    First: Copy,
-    Second: Copy,
-    Out: Copy,
-{
-    #[repr(C)]
-    #[derive(Copy, Clone)]
-    struct Both<A, B>(A, B);
+/// Copies `A` bytes of `a` followed by `B` bytes of `b` into a `[u8; N]`.
+///
+/// # Safety
+///
+/// `a` must be at least `A` bytes long, `b` must be at least `B` bytes long and `N` must be
+/// `A + B`.
+pub const unsafe fn concat<const A: usize, const B: usize, const N: usize>(
+    a: &[u8],
+    b: &[u8],
+) -> [u8; N] {
+    let out = copy_into([0u8; N], 0, std::slice::from_raw_parts(a.as_ptr(), A));
+    copy_into(out, A, std::slice::from_raw_parts(b.as_ptr(), B))
+}
+
+/// Safe version of [`concat`] that checks the lengths of its arguments.
+///
+/// Panics, and so fails const evaluation when called in a constant, if `a` isn't `A` bytes long,
+/// `b` isn't `B` bytes long or `N` isn't `A + B`.
+pub const fn checked_concat<const A: usize, const B: usize, const N: usize>(
+    a: &[u8],
+    b: &[u8],
+) -> [u8; N] {
+    check_lengths(a.len(), b.len(), A, B, N);
+
+    unsafe { concat::<A, B, N>(a, b) }
+}
+
+/// Converts bytes to a string, checking that they're valid UTF-8.
+///
+/// Panics, and so fails const evaluation when called in a constant, with the byte offset of the
+/// first invalid sequence if they aren't.
+pub const fn to_str(bytes: &[u8]) -> &str {
+    output_to_str("const_concat", bytes)
+}
+
+/// Like [`to_str`], but names `macro_name` in the panic message, for the output of the other
+/// macros that take byte strings.
+#[doc(hidden)]
+pub const fn output_to_str<'a>(macro_name: &str, bytes: &'a [u8]) -> &'a str {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(err) => {
+            let mut msg = ConstStr::<96>::new();
+            msg.push_str(macro_name);
+            msg.push_str(": invalid UTF-8 at byte offset ");
+            msg.push_str(fmt::Arg(err.valid_up_to()).to_str().as_str());
+            panic!("{}", msg.as_str())
+        }
+    }
+}
+
+/// Converts bytes to a string without checking that they're valid UTF-8.
+///
+/// # Safety
+///
+/// `bytes` must be valid UTF-8.
+pub const unsafe fn from_utf8_unchecked(bytes: &[u8]) -> &str {
+    std::str::from_utf8_unchecked(bytes)
+}
+
+/// Concatenates two slices of any `Copy` type into a `[T; N]`.
+///
+/// Checks its arguments the same way as [`checked_concat`].
+pub const fn concat_slices<T: Copy, const A: usize, const B: usize, const N: usize>(
+    a: &[T],
+    b: &[T],
+) -> [T; N] {
+    check_lengths(a.len(), b.len(), A, B, N);
+
+    let mut out = [std::mem::MaybeUninit::<T>::uninit(); N];
 
-    let arr: Both<First, Second> = Both(
-        *transmute::<_, *const First>(a.as_ptr()),
-        *transmute::<_, *const Second>(b.as_ptr()),
-    );
+    let mut i = 0;
+    while i < A {
+        out[i] = std::mem::MaybeUninit::new(a[i]);
+        i += 1;
+    }
+
+    let mut j = 0;
+    while j < B {
+        out[A + j] = std::mem::MaybeUninit::new(b[j]);
+        j += 1;
+    }
 
-    transmute(arr)
+    unsafe { transmute::<[std::mem::MaybeUninit<T>; N], [T; N]>(out) }
 }
 
+/// Repeats `s` `count` times into a `[u8; N]`.
+///
+/// Panics if `N` isn't `s.len() * count`.
+pub const fn repeat<const N: usize>(s: &[u8], count: usize) -> [u8; N] {
+    if s.len() * count != N {
+        panic!("const_repeat: output length `N` is not the input length times `count`");
+    }
+
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < count {
+        out = copy_into(out, i * s.len(), s);
+        i += 1;
+    }
+
+    out
+}
+
+/// Copies as much of `src` as fits into `out` at `len`, returning the new length as if all of it
+/// had fit.
+const fn write_truncated<const N: usize>(
+    mut out: [u8; N],
+    len: usize,
+    src: &[u8],
+) -> ([u8; N], usize) {
+    if len < N {
+        let fits = if src.len() < N - len { src.len() } else { N - len };
+        out = copy_into(out, len, src.split_at(fits).0);
+    }
+
+    (out, len + src.len())
+}
+
+/// Copies `src` into `out` starting at `at`.
+const fn copy_into<const N: usize>(mut out: [u8; N], at: usize, src: &[u8]) -> [u8; N] {
+    let mut i = 0;
+    while i < src.len() {
+        out[at + i] = src[i];
+        i += 1;
+    }
+
+    out
+}
+
+const fn check_lengths(a_len: usize, b_len: usize, a: usize, b: usize, n: usize) {
+    if a_len != a {
+        panic!("const_concat: length of first argument does not match `A`");
+    }
+    if b_len != b {
+        panic!("const_concat: length of second argument does not match `B`");
+    }
+    if a + b != n {
+        panic!("const_concat: output length `N` is not `A + B`");
+    }
+}
+
+/// Concatenates constants into a `&'static str`.
+///
+/// Like `std::concat!`, the arguments can be strings, integers, floats, `bool`s or `char`s, but
+/// they can be any constant expression rather than only literals. Integer and float literals need
+/// a suffix (`42u32`) so that their type is known.
+///
+/// Byte strings are accepted too. The output is checked to be valid UTF-8, and compilation fails
+/// with the byte offset of the first invalid sequence if it isn't; [`const_concat_unchecked!`]
+/// skips the check.
+///
+/// The arguments can't depend on generic parameters, including `Self` in traits, because the
+/// output is sized to fit exactly. For those constants, give a maximum length with a leading
+/// `capacity = N;`, and compilation fails if the output turns out to be longer. This works in
+/// generic functions as well as in constants:
+///
+/// ```
+/// # #[macro_use] extern crate const_concat;
+/// trait Plugin {
+///     const PREFIX: &'static str;
+///     const NAME: &'static str;
+///     const ID: &'static str = const_concat!(capacity = 64; Self::PREFIX, "::", Self::NAME);
+/// }
+///
+/// fn describe<T: Plugin>() -> &'static str {
+///     const_concat!(capacity = 64; T::NAME, " from ", T::PREFIX)
+/// }
+/// # fn main() {}
+/// ```
 #[macro_export]
 macro_rules! const_concat {
+    (capacity = $cap:expr; $($arg:expr),*) => {
+        // An inline `const` rather than a named one, because the arguments can mention generic
+        // parameters. It's still evaluated at compile time, and borrowing it gives a `'static`
+        // reference wherever the macro is used.
+        (&const {
+            $crate::fmt::concat_with_capacity::<{ $cap }>(&[$($crate::fmt::Arg($arg).to_str()),*])
+        })
+        .as_str()
+    };
+    (capacity = $cap:expr; $($arg:expr),*,) => {
+        $crate::const_concat!(capacity = $cap; $($arg),*)
+    };
     () => {
         ""
     };
-    ($a:expr) => {
-        $a
+    ($($arg:expr),+) => {{
+        const __BYTES: &[u8] = $crate::const_concat!(@bytes $($arg),+);
+        const __S: &str = $crate::to_str(__BYTES);
+        __S
+    }};
+    ($($arg:expr),+,) => {
+        $crate::const_concat!($($arg),+)
     };
-    ($a:expr, $b:expr) => {{
-        let bytes: &'static [u8] = unsafe {
-            &$crate::concat::<
-                [u8; $a.len()],
-                [u8; $b.len()],
-                [u8; $a.len() + $b.len()],
-            >($a.as_bytes(), $b.as_bytes())
-        };
+    (@bytes) => {
+        &[]
+    };
+    (@bytes $a:expr) => {{
+        const __A: &[u8] = $crate::fmt::Arg($a).to_str().as_bytes();
+        __A
+    }};
+    (@bytes $a:expr, $b:expr) => {{
+        const __A: &[u8] = $crate::fmt::Arg($a).to_str().as_bytes();
+        const __B: &[u8] = $crate::fmt::Arg($b).to_str().as_bytes();
+        const __BYTES: [u8; __A.len() + __B.len()] =
+            $crate::checked_concat::<{ __A.len() }, { __B.len() }, { __A.len() + __B.len() }>(
+                __A, __B,
+            );
+
+        &__BYTES
+    }};
+    (@bytes $a:expr, $($rest:expr),+) => {{
+        const __TAIL: &[u8] = $crate::const_concat!(@bytes $($rest),+);
+        $crate::const_concat!(@bytes $a, __TAIL)
+    }};
+}
 
-        unsafe { $crate::transmute::<_, &'static str>(bytes) }
+/// Like [`const_concat!`], but without checking that the output is valid UTF-8.
+///
+/// This only makes a difference when some of the arguments are byte strings, and has to be used
+/// inside an `unsafe` block: the caller must make sure that the concatenated bytes are UTF-8.
+/// Byte strings can be split anywhere, such as in the middle of a character, as long as the
+/// whole output is valid.
+#[macro_export]
+macro_rules! const_concat_unchecked {
+    ($($arg:expr),*) => {{
+        const __BYTES: &[u8] = $crate::const_concat!(@bytes $($arg),*);
+        $crate::from_utf8_unchecked(__BYTES)
     }};
-    ($a:expr, $($rest:expr),*) => {{
-        const TAIL: &str = const_concat!($($rest),*);
-        const_concat!($a, TAIL)
+    ($($arg:expr),*,) => {
+        $crate::const_concat_unchecked!($($arg),*)
+    };
+}
+
+/// Reads an environment variable at compile time with `option_env!`, falling back to a default.
+///
+/// `const_env!("NAME", default = "x")` is the variable's value, or the default if it isn't set.
+/// The default is converted the same way as arguments to [`const_concat!`], so it can be an
+/// integer or another constant. Without a default, this is the `Option<&'static str>` from
+/// `option_env!`, which [`const_concat!`] also accepts and skips when it's `None`.
+#[macro_export]
+macro_rules! const_env {
+    ($name:expr, default = $default:expr) => {{
+        const __DEFAULT: &str = $crate::fmt::Arg($default).to_str().as_str();
+        const __VALUE: &str = match option_env!($name) {
+            Some(value) => value,
+            None => __DEFAULT,
+        };
+        __VALUE
     }};
-    ($a:expr, $($rest:expr),*,) => {
-        const_concat!($a, $($rest),*)
+    ($name:expr) => {
+        option_env!($name)
     };
 }
 
+/// Repeats a constant string, or `char`, `count` times into a `&'static str`.
+#[macro_export]
+macro_rules! const_repeat {
+    ($s:expr, $count:expr) => {{
+        const __S: &str = $crate::fmt::Arg($s).to_str().as_str();
+        const __COUNT: usize = $count;
+        const __BYTES: [u8; __S.len() * __COUNT] =
+            $crate::repeat::<{ __S.len() * __COUNT }>(__S.as_bytes(), __COUNT);
+
+        unsafe { $crate::from_utf8_unchecked(&__BYTES) }
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -72,4 +318,83 @@ mod tests {
         assert_eq!(GREETING, "Hello, world!");
         assert_eq!(GREETING_TRAILING_COMMA, "Hello, world!");
     }
+
+    #[test]
+    fn argument_names_match_internal_constants() {
+        const A: &str = "a";
+        const B: &str = "b";
+        const TAIL: &str = "tail";
+        const BYTES: &str = "bytes";
+
+        assert_eq!(const_concat!(A, B, TAIL, BYTES), "abtailbytes");
+    }
+
+    #[test]
+    fn checked_concat() {
+        const BYTES: [u8; 5] = super::checked_concat::<2, 3, 5>(b"ab", b"cde");
+
+        assert_eq!(&BYTES, b"abcde");
+    }
+
+    #[test]
+    #[should_panic(expected = "output length `N` is not `A + B`")]
+    fn checked_concat_wrong_output_length() {
+        super::checked_concat::<2, 3, 4>(b"ab", b"cde");
+    }
+
+    #[test]
+    #[should_panic(expected = "length of first argument does not match `A`")]
+    fn checked_concat_wrong_input_length() {
+        super::checked_concat::<3, 3, 6>(b"ab", b"cde");
+    }
+
+    #[test]
+    fn repeat() {
+        const INDENT: &str = "  ";
+        const DEPTH: usize = 3;
+        const RULE: &str = const_repeat!("=", 80);
+        const NESTED: &str = const_concat!(const_repeat!(INDENT, DEPTH), "- item");
+
+        assert_eq!(RULE, "=".repeat(80));
+        assert_eq!(NESTED, "      - item");
+        assert_eq!(const_repeat!('é', 2), "éé");
+        assert_eq!(const_repeat!("abc", 0), "");
+    }
+
+    #[test]
+    fn env() {
+        const NAME: &str = const_env!("CARGO_PKG_NAME", default = "unknown");
+        const MISSING: &str = const_env!("CONST_CONCAT_TEST_UNSET_VARIABLE", default = "fallback");
+        const PORT: &str = const_env!("CONST_CONCAT_TEST_UNSET_VARIABLE", default = 8080u16);
+        const BANNER: &str = const_concat!(
+            NAME,
+            const_env!("CONST_CONCAT_TEST_UNSET_VARIABLE"),
+            " ",
+            const_env!("CARGO_PKG_NAME"),
+        );
+
+        assert_eq!(NAME, "const-concat");
+        assert_eq!(MISSING, "fallback");
+        assert_eq!(PORT, "8080");
+        assert_eq!(BANNER, "const-concat const-concat");
+    }
+
+    #[test]
+    fn byte_strings() {
+        const MAGIC: &[u8] = b"GIF";
+        const HEADER: &str = const_concat!(MAGIC, b"89a", ' ', 7u8);
+        // The two halves of "é" are only valid together.
+        const SPLIT: &str = const_concat!("caf", b"\xc3", b"\xa9");
+        const UNCHECKED: &str = unsafe { const_concat_unchecked!("caf", b"\xc3", b"\xa9") };
+
+        assert_eq!(HEADER, "GIF89a 7");
+        assert_eq!(SPLIT, "café");
+        assert_eq!(UNCHECKED, "café");
+    }
+
+    #[test]
+    #[should_panic(expected = "const_concat: invalid UTF-8 at byte offset 5")]
+    fn invalid_utf8() {
+        super::to_str(b"caf\xc3\xa9\xc3");
+    }
 }
@@ -0,0 +1,143 @@
+//! Padding strings to a width in constants.
+
+/// Where [`pad`] puts a string within its width.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Align {
+    /// Fill after the string.
+    Left,
+    /// Fill before the string.
+    Right,
+    /// Fill on both sides, with the extra char after the string if the fill is uneven, like
+    /// `format!("{:^5}", "ab")`.
+    Center,
+}
+
+/// Returns the length in bytes of `s` padded with `fill` to `width` chars.
+pub const fn padded_len(s: &str, width: usize, fill: char) -> usize {
+    s.len() + fill_count(s, width) * fill.len_utf8()
+}
+
+/// Pads `s` with `fill` to `width` chars into a `[u8; N]`, like `format!("{:width$}", s)` with
+/// a fill and alignment. Strings that are already at least `width` chars are left unchanged.
+///
+/// Panics if `N` isn't [`padded_len`].
+pub const fn pad<const N: usize>(s: &str, width: usize, fill: char, align: Align) -> [u8; N] {
+    if padded_len(s, width, fill) != N {
+        panic!("const_pad: output length `N` is not the padded length");
+    }
+
+    let count = fill_count(s, width);
+    let before = match align {
+        Align::Left => 0,
+        Align::Right => count,
+        Align::Center => count / 2,
+    };
+
+    let mut buf = [0u8; 4];
+    let fill = fill.encode_utf8(&mut buf).as_bytes();
+    let mut out = [0u8; N];
+    let mut len = 0;
+    let mut i = 0;
+    while i < count {
+        if i == before {
+            (out, len) = crate::write_truncated(out, len, s.as_bytes());
+        }
+        (out, len) = crate::write_truncated(out, len, fill);
+        i += 1;
+    }
+    if before == count {
+        (out, _) = crate::write_truncated(out, len, s.as_bytes());
+    }
+
+    out
+}
+
+/// Returns how many fill chars `s` needs to be `width` chars wide.
+const fn fill_count(s: &str, width: usize) -> usize {
+    width.saturating_sub(crate::substr::char_count(s))
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_pad {
+    (@align left) => {
+        $crate::pad::Align::Left
+    };
+    (@align right) => {
+        $crate::pad::Align::Right
+    };
+    (@align center) => {
+        $crate::pad::Align::Center
+    };
+    ($s:expr, $width:expr, $fill:expr, $align:expr) => {{
+        const __S: &str = $crate::fmt::Arg($s).to_str().as_str();
+        const __WIDTH: usize = $width;
+        const __FILL: char = $fill;
+        const __LEN: usize = $crate::pad::padded_len(__S, __WIDTH, __FILL);
+        const __BYTES: [u8; __LEN] = $crate::pad::pad::<__LEN>(__S, __WIDTH, __FILL, $align);
+
+        unsafe { $crate::from_utf8_unchecked(&__BYTES) }
+    }};
+}
+
+/// Pads a constant to a width in chars, into a `&'static str`.
+///
+/// `const_pad!(NAME, width = 20, fill = '.', align = right)` is `format!("{:.>20}", NAME)`. The
+/// fill defaults to a space and the alignment, which is one of `left`, `right` or `center`,
+/// defaults to `left`. The string is converted the same way as arguments to [`const_concat!`], so
+/// numbers can be padded too.
+#[macro_export]
+macro_rules! const_pad {
+    ($s:expr, width = $width:expr) => {
+        $crate::const_pad!($s, width = $width, fill = ' ', align = left)
+    };
+    ($s:expr, width = $width:expr, fill = $fill:expr) => {
+        $crate::const_pad!($s, width = $width, fill = $fill, align = left)
+    };
+    ($s:expr, width = $width:expr, align = $align:ident) => {
+        $crate::const_pad!($s, width = $width, fill = ' ', align = $align)
+    };
+    ($s:expr, width = $width:expr, fill = $fill:expr, align = $align:ident) => {
+        $crate::__const_pad!($s, $width, $fill, $crate::__const_pad!(@align $align))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pad, Align};
+    use crate::const_concat;
+
+    #[test]
+    fn alignments() {
+        const NAME: &str = "añb";
+
+        assert_eq!(const_pad!(NAME, width = 6), format!("{:6}", NAME));
+        assert_eq!(const_pad!(NAME, width = 6, align = right), format!("{:>6}", NAME));
+        assert_eq!(const_pad!(NAME, width = 6, align = center), format!("{:^6}", NAME));
+        assert_eq!(
+            const_pad!(NAME, width = 8, fill = '─', align = center),
+            format!("{:─^8}", NAME)
+        );
+        assert_eq!(const_pad!(NAME, width = 2, fill = '*'), NAME);
+        assert_eq!(const_pad!("", width = 3, fill = '-', align = right), "---");
+    }
+
+    #[test]
+    fn table_row() {
+        const ROW: &str = const_concat!(
+            "|",
+            const_pad!("name", width = 8),
+            "|",
+            const_pad!(42u32, width = 6, fill = '0', align = right),
+            "|",
+        );
+
+        assert_eq!(ROW, "|name    |000042|");
+    }
+
+    #[test]
+    #[should_panic(expected = "const_pad: output length `N` is not the padded length")]
+    fn wrong_length() {
+        pad::<4>("ab", 4, 'é', Align::Left);
+    }
+}
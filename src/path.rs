@@ -0,0 +1,289 @@
+//! Joining path segments in constants.
+
+/// Returns the length of `parts` joined by [`join`] with the same arguments.
+pub const fn joined_len(parts: &[&str], separator: char, resolve: bool) -> usize {
+    join_into::<0>(parts, separator, resolve).1
+}
+
+/// Joins `parts` into a path with exactly one `separator` between components, into a `[u8; N]`.
+///
+/// Both `/` and `separator` are treated as separators in `parts`, and runs of them are collapsed
+/// into one. A leading separator on the first part and a trailing separator on the last one are
+/// kept. If `resolve` is `true`, `.` components are removed and `..` components remove the
+/// component before them, or are themselves removed at the start of an absolute path.
+///
+/// Panics if `separator` isn't ASCII or `N` isn't [`joined_len`] of the same arguments.
+pub const fn join<const N: usize>(parts: &[&str], separator: char, resolve: bool) -> [u8; N] {
+    let (out, len) = join_into::<N>(parts, separator, resolve);
+    if len != N {
+        panic!("const_path: output length `N` is not the length of the joined path");
+    }
+
+    out
+}
+
+#[derive(Copy, Clone)]
+struct Component {
+    part: usize,
+    start: usize,
+    end: usize,
+}
+
+/// Writes the first `N` bytes of the joined path and returns the length of all of it.
+const fn join_into<const N: usize>(
+    parts: &[&str],
+    separator: char,
+    resolve: bool,
+) -> ([u8; N], usize) {
+    if !separator.is_ascii() {
+        panic!("const_path: separator is not ASCII");
+    }
+    let sep = separator as u8;
+
+    let mut out = [0u8; N];
+    let mut len = 0;
+
+    let (mut first, mut last) = (None, None);
+    let mut i = 0;
+    while i < parts.len() {
+        if !parts[i].is_empty() {
+            if first.is_none() {
+                first = Some(i);
+            }
+            last = Some(i);
+        }
+        i += 1;
+    }
+    let (first, last) = match (first, last) {
+        (Some(first), Some(last)) => (parts[first].as_bytes(), parts[last].as_bytes()),
+        _ => return (out, 0),
+    };
+    let absolute = is_separator(first[0], sep);
+    let trailing = is_separator(last[last.len() - 1], sep);
+
+    if absolute {
+        (out, len) = crate::write_truncated(out, len, &[sep]);
+    }
+
+    let mut written = 0;
+    let mut seen = 0;
+    let mut next = next_component(parts, sep, 0, 0);
+    while let Some(component) = next {
+        seen += 1;
+        if !resolve || is_kept(parts, sep, component, absolute) {
+            if written > 0 {
+                (out, len) = crate::write_truncated(out, len, &[sep]);
+            }
+            let bytes = parts[component.part].as_bytes();
+            let bytes = bytes.split_at(component.end).0.split_at(component.start).1;
+            (out, len) = crate::write_truncated(out, len, bytes);
+            written += 1;
+        }
+        next = next_component(parts, sep, component.part, component.end);
+    }
+
+    if written == 0 && seen > 0 && !absolute {
+        (out, len) = crate::write_truncated(out, len, b".");
+    } else if trailing && written > 0 {
+        (out, len) = crate::write_truncated(out, len, &[sep]);
+    }
+
+    (out, len)
+}
+
+/// Returns `true` if `component` is still in the path once `.` and `..` are resolved.
+const fn is_kept(parts: &[&str], sep: u8, component: Component, absolute: bool) -> bool {
+    match kind(parts, component) {
+        Kind::Current => false,
+        // A `..` is kept if there's nothing before it for it to remove.
+        Kind::Parent => {
+            let mut depth = 0;
+            let mut prev = prev_component(parts, sep, component);
+            while let Some(c) = prev {
+                match kind(parts, c) {
+                    Kind::Current => {}
+                    Kind::Parent => depth += 1,
+                    Kind::Normal if depth == 0 => return false,
+                    Kind::Normal => depth -= 1,
+                }
+                prev = prev_component(parts, sep, c);
+            }
+            !absolute
+        }
+        // A normal component is kept if no `..` after it removes it.
+        Kind::Normal => {
+            let mut depth = 0;
+            let mut next = next_component(parts, sep, component.part, component.end);
+            while let Some(c) = next {
+                match kind(parts, c) {
+                    Kind::Current => {}
+                    Kind::Normal => depth += 1,
+                    Kind::Parent if depth == 0 => return false,
+                    Kind::Parent => depth -= 1,
+                }
+                next = next_component(parts, sep, c.part, c.end);
+            }
+            true
+        }
+    }
+}
+
+enum Kind {
+    Current,
+    Parent,
+    Normal,
+}
+
+const fn kind(parts: &[&str], component: Component) -> Kind {
+    let bytes = parts[component.part].as_bytes();
+    match component.end - component.start {
+        1 if bytes[component.start] == b'.' => Kind::Current,
+        2 if bytes[component.start] == b'.' && bytes[component.start + 1] == b'.' => Kind::Parent,
+        _ => Kind::Normal,
+    }
+}
+
+/// Returns the first component starting at or after byte `at` of `parts[part]`.
+const fn next_component(
+    parts: &[&str],
+    sep: u8,
+    mut part: usize,
+    mut at: usize,
+) -> Option<Component> {
+    while part < parts.len() {
+        let bytes = parts[part].as_bytes();
+        while at < bytes.len() && is_separator(bytes[at], sep) {
+            at += 1;
+        }
+        if at < bytes.len() {
+            let mut end = at;
+            while end < bytes.len() && !is_separator(bytes[end], sep) {
+                end += 1;
+            }
+            return Some(Component { part, start: at, end });
+        }
+
+        part += 1;
+        at = 0;
+    }
+
+    None
+}
+
+/// Returns the last component ending before `component` starts.
+const fn prev_component(parts: &[&str], sep: u8, component: Component) -> Option<Component> {
+    let mut part = component.part;
+    let mut end = component.start;
+    loop {
+        let bytes = parts[part].as_bytes();
+        while end > 0 && is_separator(bytes[end - 1], sep) {
+            end -= 1;
+        }
+        if end > 0 {
+            let mut start = end;
+            while start > 0 && !is_separator(bytes[start - 1], sep) {
+                start -= 1;
+            }
+            return Some(Component { part, start, end });
+        }
+
+        if part == 0 {
+            return None;
+        }
+        part -= 1;
+        end = parts[part].len();
+    }
+}
+
+const fn is_separator(b: u8, sep: u8) -> bool {
+    b == b'/' || b == sep
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_join_path {
+    ($separator:expr, $resolve:expr; $($arg:expr),*) => {{
+        const __PARTS: &[&str] = &[$($crate::fmt::Arg($arg).to_str().as_str()),*];
+        const __LEN: usize = $crate::path::joined_len(__PARTS, $separator, $resolve);
+        const __BYTES: [u8; __LEN] = $crate::path::join::<__LEN>(__PARTS, $separator, $resolve);
+
+        unsafe { $crate::from_utf8_unchecked(&__BYTES) }
+    }};
+}
+
+/// Joins constants into a `&'static str` path with exactly one `std::path::MAIN_SEPARATOR`
+/// between components.
+///
+/// The arguments are converted the same way as in [`const_concat!`], and joined as described in
+/// [`path::join`](crate::path::join). A leading `resolve;` resolves `.` and `..` components:
+/// `const_path!(resolve; ROOT, "../lib", FILE)`.
+#[macro_export]
+macro_rules! const_path {
+    (resolve; $($arg:expr),*) => {
+        $crate::__const_join_path!(::std::path::MAIN_SEPARATOR, true; $($arg),*)
+    };
+    (resolve; $($arg:expr),+,) => {
+        $crate::const_path!(resolve; $($arg),+)
+    };
+    ($($arg:expr),*) => {
+        $crate::__const_join_path!(::std::path::MAIN_SEPARATOR, false; $($arg),*)
+    };
+    ($($arg:expr),+,) => {
+        $crate::const_path!($($arg),+)
+    };
+}
+
+/// Like [`const_path!`], but always separates components with `/`, for URL paths.
+///
+/// This normalizes every run of slashes, so it's meant for the path of a URL rather than a
+/// whole URL with a scheme.
+#[macro_export]
+macro_rules! const_url_path {
+    (resolve; $($arg:expr),*) => {
+        $crate::__const_join_path!('/', true; $($arg),*)
+    };
+    (resolve; $($arg:expr),+,) => {
+        $crate::const_url_path!(resolve; $($arg),+)
+    };
+    ($($arg:expr),*) => {
+        $crate::__const_join_path!('/', false; $($arg),*)
+    };
+    ($($arg:expr),+,) => {
+        $crate::const_url_path!($($arg),+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::MAIN_SEPARATOR;
+
+    #[test]
+    fn url_paths() {
+        const ROOT: &str = "/api/";
+        const VERSION: u8 = 2;
+        const FILE: &str = "/users//list";
+
+        assert_eq!(const_url_path!(ROOT, "v", "/", VERSION, FILE), "/api/v/2/users/list");
+        assert_eq!(const_url_path!("static", "css/",), "static/css/");
+        assert_eq!(const_url_path!("", "//", ""), "/");
+        assert_eq!(const_url_path!(), "");
+    }
+
+    #[test]
+    fn resolve() {
+        assert_eq!(const_url_path!(resolve; "/srv/app", "../lib/./x", ".."), "/srv/lib");
+        assert_eq!(const_url_path!(resolve; "/", "../../etc"), "/etc");
+        assert_eq!(const_url_path!(resolve; "a/b", "..",), "a");
+        assert_eq!(const_url_path!(resolve; "a/b", "../../../c/"), "../c/");
+        assert_eq!(const_url_path!(resolve; "a", "b/../.."), ".");
+        assert_eq!(const_url_path!("a", "./b/.."), "a/./b/..");
+    }
+
+    #[test]
+    fn native_separator() {
+        const ROOT: &str = "/usr";
+        const PATH: &str = const_path!(ROOT, "share//", "/doc");
+
+        assert_eq!(PATH, format!("{0}usr{0}share{0}doc", MAIN_SEPARATOR));
+    }
+}
@@ -0,0 +1,133 @@
+//! Comparing and searching strings in constants.
+//!
+//! ```
+//! # #[macro_use] extern crate const_concat;
+//! # fn main() {
+//! use const_concat::query;
+//!
+//! const PREFIX: &str = "/api";
+//! const ROUTE: &str = const_concat!(PREFIX, "/v1/users");
+//!
+//! const _: () = assert!(query::starts_with(ROUTE, "/api/"));
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+
+/// Returns `true` if `a` and `b` are the same string.
+pub const fn eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && matches_at(a.as_bytes(), b.as_bytes(), 0)
+}
+
+/// Compares `a` and `b` lexicographically by bytes, like `Ord for str`.
+pub const fn cmp(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] < b[i] {
+            return Ordering::Less;
+        } else if a[i] > b[i] {
+            return Ordering::Greater;
+        }
+        i += 1;
+    }
+
+    if a.len() < b.len() {
+        Ordering::Less
+    } else if a.len() > b.len() {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+/// Returns `true` if `s` starts with `prefix`.
+pub const fn starts_with(s: &str, prefix: &str) -> bool {
+    prefix.len() <= s.len() && matches_at(s.as_bytes(), prefix.as_bytes(), 0)
+}
+
+/// Returns `true` if `s` ends with `suffix`.
+pub const fn ends_with(s: &str, suffix: &str) -> bool {
+    suffix.len() <= s.len() && matches_at(s.as_bytes(), suffix.as_bytes(), s.len() - suffix.len())
+}
+
+/// Returns `true` if `s` contains `needle`.
+pub const fn contains(s: &str, needle: &str) -> bool {
+    find(s, needle).is_some()
+}
+
+/// Returns the byte index of the first occurrence of `needle` in `s`.
+pub const fn find(s: &str, needle: &str) -> Option<usize> {
+    find_from(s, needle, 0)
+}
+
+/// Returns the byte index of the first occurrence of `needle` in `s` at or after `start`.
+pub(crate) const fn find_from(s: &str, needle: &str, start: usize) -> Option<usize> {
+    let (s, needle) = (s.as_bytes(), needle.as_bytes());
+    if needle.len() > s.len() {
+        return None;
+    }
+
+    let mut i = start;
+    while i <= s.len() - needle.len() {
+        if matches_at(s, needle, i) {
+            return Some(i);
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Returns `true` if `needle` appears in `s` at byte index `at`, which must leave room for it.
+const fn matches_at(s: &[u8], needle: &[u8], at: usize) -> bool {
+    let mut i = 0;
+    while i < needle.len() {
+        if s[at + i] != needle[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::const_concat;
+
+    const ROUTE: &str = const_concat!("/api", "/v1/", "users");
+
+    const _: () = assert!(starts_with(ROUTE, "/api"));
+    const _: () = assert!(ends_with(ROUTE, "users"));
+    const _: () = assert!(eq(ROUTE, "/api/v1/users"));
+
+    #[test]
+    fn comparisons() {
+        const ORDER: Ordering = cmp("abc", "abd");
+
+        assert_eq!(ORDER, Ordering::Less);
+        for (a, b) in [("", ""), ("a", ""), ("ab", "abc"), ("é", "z"), ("b", "a")] {
+            assert_eq!(cmp(a, b), a.cmp(b));
+            assert_eq!(eq(a, b), a == b);
+        }
+        assert!(!starts_with("/ap", "/api"));
+        assert!(!ends_with(ROUTE, "user"));
+        assert!(starts_with(ROUTE, ""));
+    }
+
+    #[test]
+    fn search() {
+        const VERSION_AT: Option<usize> = find(ROUTE, "/v1");
+
+        assert_eq!(VERSION_AT, Some(4));
+        assert_eq!(find(ROUTE, "/"), Some(0));
+        assert_eq!(find(ROUTE, "/v2"), None);
+        assert_eq!(find("ab", "abc"), None);
+        assert_eq!(find("ab", ""), Some(0));
+        assert!(contains(ROUTE, "v1/u"));
+        assert!(!contains("", "x"));
+    }
+}
@@ -0,0 +1,93 @@
+//! Replacing patterns in strings in constants.
+
+/// Replaces every occurrence of `from` in `s` with `to`, like `str::replace`, into a `[u8; N]`.
+///
+/// Panics if `N` isn't [`replaced_len`] of the same arguments.
+pub const fn replace<const N: usize>(s: &str, from: &str, to: &str) -> [u8; N] {
+    let (out, len) = replace_into::<N>(s, from, to);
+    if len != N {
+        panic!("const_replace: output length `N` is not the length of the replaced string");
+    }
+
+    out
+}
+
+/// Returns the length of `s` with every occurrence of `from` replaced with `to`.
+pub const fn replaced_len(s: &str, from: &str, to: &str) -> usize {
+    replace_into::<0>(s, from, to).1
+}
+
+/// Writes the first `N` bytes of the replaced string and returns the length of all of it.
+const fn replace_into<const N: usize>(s: &str, from: &str, to: &str) -> ([u8; N], usize) {
+    let bytes = s.as_bytes();
+    let mut out = [0u8; N];
+    let mut len = 0;
+
+    let mut i = 0;
+    loop {
+        let (end, found) = match crate::query::find_from(s, from, i) {
+            Some(at) => (at, true),
+            None => (bytes.len(), false),
+        };
+        (out, len) = crate::write_truncated(out, len, bytes.split_at(end).0.split_at(i).1);
+        if !found {
+            break;
+        }
+
+        (out, len) = crate::write_truncated(out, len, to.as_bytes());
+        i = end + from.len();
+        if from.is_empty() {
+            // An empty pattern matches between every char, so step over the next one.
+            if end == bytes.len() {
+                break;
+            }
+            let mut next = end + 1;
+            while next < bytes.len() && crate::utf8::is_continuation(bytes[next]) {
+                next += 1;
+            }
+            (out, len) = crate::write_truncated(out, len, bytes.split_at(next).0.split_at(end).1);
+            i = next;
+        }
+    }
+
+    (out, len)
+}
+
+/// Replaces every occurrence of a pattern in a constant string, into a `&'static str`.
+///
+/// `const_replace!(PATH, "::", '.')` is `PATH.replace("::", ".")`. The string, pattern and
+/// replacement are all converted the same way as arguments to [`const_concat!`].
+#[macro_export]
+macro_rules! const_replace {
+    ($s:expr, $from:expr, $to:expr) => {{
+        const __S: &str = $crate::fmt::Arg($s).to_str().as_str();
+        const __FROM: &str = $crate::fmt::Arg($from).to_str().as_str();
+        const __TO: &str = $crate::fmt::Arg($to).to_str().as_str();
+        const __LEN: usize = $crate::replace::replaced_len(__S, __FROM, __TO);
+        const __BYTES: [u8; __LEN] = $crate::replace::replace::<__LEN>(__S, __FROM, __TO);
+
+        unsafe { $crate::from_utf8_unchecked(&__BYTES) }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::const_concat;
+
+    #[test]
+    fn replacements() {
+        const MODULE: &str = "my_crate::net::http";
+        const METRIC: &str = const_concat!(const_replace!(MODULE, "::", '.'), ".requests");
+
+        assert_eq!(METRIC, "my_crate.net.http.requests");
+        assert_eq!(const_replace!(MODULE, "missing", "x"), MODULE);
+        assert_eq!(const_replace!("aaa", "a", ""), "");
+        let cases = [("ab", "", "-"), ("", "", "x"), ("héllo", "", "."), ("aaaa", "aa", "b")];
+        for (s, from, to) in cases {
+            assert_eq!(super::replaced_len(s, from, to), s.replace(from, to).len());
+        }
+        assert_eq!(const_replace!("héllo", "", "."), ".h.é.l.l.o.");
+        assert_eq!(const_replace!(1_000_000u32, '0', "O"), "1OOOOOO");
+        assert_eq!(const_replace!(b"a-b", '-', '_'), "a_b");
+    }
+}
@@ -0,0 +1,98 @@
+/// Wrapper used by [`const_concat_slices!`] to turn each of its arguments into a slice.
+///
+/// Works like [`ByteArg`](crate::bytes::ByteArg), with `as_slice` in place of `as_bytes`.
+pub struct SliceArg<T>(pub T);
+
+impl<'a, T> SliceArg<&'a [T]> {
+    pub const fn as_slice(&self) -> &'a [T] {
+        self.0
+    }
+}
+
+impl<'a, T, const N: usize> SliceArg<&'a [T; N]> {
+    pub const fn as_slice(&self) -> &'a [T] {
+        self.0
+    }
+}
+
+impl<T, const N: usize> SliceArg<[T; N]> {
+    pub const fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+}
+
+/// Concatenates constant slices and arrays of `T` into a `[T; N]`.
+///
+/// `T` can be any `Copy` type. The element type comes first, separated from the arguments by a
+/// semicolon: `const_concat_slices!(u16; LOW, HIGH)`.
+#[macro_export]
+macro_rules! const_concat_slices {
+    ($t:ty;) => {{
+        const __OUT: [$t; 0] = [];
+        __OUT
+    }};
+    ($t:ty; $a:expr) => {{
+        const __A: &[$t] = $crate::slices::SliceArg($a).as_slice();
+        const __OUT: [$t; __A.len()] =
+            $crate::concat_slices::<$t, { __A.len() }, 0, { __A.len() }>(__A, &[]);
+
+        __OUT
+    }};
+    ($t:ty; $a:expr, $b:expr) => {{
+        const __A: &[$t] = $crate::slices::SliceArg($a).as_slice();
+        const __B: &[$t] = $crate::slices::SliceArg($b).as_slice();
+        const __OUT: [$t; __A.len() + __B.len()] = $crate::concat_slices::<
+            $t,
+            { __A.len() },
+            { __B.len() },
+            { __A.len() + __B.len() },
+        >(__A, __B);
+
+        __OUT
+    }};
+    ($t:ty; $a:expr, $($rest:expr),*) => {{
+        const __TAIL: &[$t] = &$crate::const_concat_slices!($t; $($rest),*);
+        $crate::const_concat_slices!($t; $a, __TAIL)
+    }};
+    ($t:ty; $a:expr, $($rest:expr),*,) => {
+        $crate::const_concat_slices!($t; $a, $($rest),*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn integer_tables() {
+        const LOW: [u16; 3] = [1, 2, 3];
+        const HIGH: &[u16] = &[0xfffe, 0xffff];
+        const TABLE: [u16; 6] = const_concat_slices!(u16; LOW, &[100], HIGH,);
+
+        assert_eq!(TABLE, [1, 2, 3, 100, 0xfffe, 0xffff]);
+    }
+
+    #[test]
+    fn non_integer_elements() {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Entry {
+            name: &'static str,
+            code: char,
+        }
+
+        const BUILTIN: [Entry; 1] = [Entry { name: "alpha", code: 'a' }];
+        const EXTRA: &[Entry] = &[Entry { name: "beta", code: 'b' }];
+        const ENTRIES: [Entry; 2] = const_concat_slices!(Entry; BUILTIN, EXTRA);
+        const NAMES: [&str; 3] = const_concat_slices!(&'static str; ["x"], ["y", "z"]);
+
+        assert_eq!(ENTRIES[1], Entry { name: "beta", code: 'b' });
+        assert_eq!(NAMES, ["x", "y", "z"]);
+    }
+
+    #[test]
+    fn empty_and_single() {
+        const EMPTY: [char; 0] = const_concat_slices!(char;);
+        const SINGLE: [char; 2] = const_concat_slices!(char; ['a', 'b']);
+
+        assert_eq!(EMPTY, []);
+        assert_eq!(SINGLE, ['a', 'b']);
+    }
+}
@@ -0,0 +1,336 @@
+//! Slicing strings in constants.
+
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+
+use crate::utf8::is_continuation;
+
+/// Returns `s[start..end]`.
+///
+/// Panics if the range is out of bounds or either end isn't on a char boundary.
+pub const fn byte_range(s: &str, start: usize, end: usize) -> &str {
+    if start > end {
+        panic!("const_substr: range start is greater than range end");
+    }
+    if end > s.len() {
+        panic!("const_substr: range end is out of bounds");
+    }
+    if !is_char_boundary(s, start) {
+        panic!("const_substr: range start is not on a char boundary");
+    }
+    if !is_char_boundary(s, end) {
+        panic!("const_substr: range end is not on a char boundary");
+    }
+
+    let bytes = s.as_bytes().split_at(end).0.split_at(start).1;
+    unsafe { crate::from_utf8_unchecked(bytes) }
+}
+
+/// Returns the chars of `s` from the `start`th up to but not including the `end`th.
+///
+/// Panics if the range is out of bounds.
+pub const fn char_range(s: &str, start: usize, end: usize) -> &str {
+    if start > end {
+        panic!("const_substr: range start is greater than range end");
+    }
+    if end > char_count(s) {
+        panic!("const_substr: range end is out of bounds");
+    }
+
+    byte_range(s, char_offset(s, start), char_offset(s, end))
+}
+
+/// Returns the longest prefix of `s` that's at most `max_bytes` long without splitting a char.
+pub const fn truncate(s: &str, max_bytes: usize) -> &str {
+    if max_bytes >= s.len() {
+        return s;
+    }
+
+    let mut end = max_bytes;
+    while !is_char_boundary(s, end) {
+        end -= 1;
+    }
+
+    byte_range(s, 0, end)
+}
+
+/// Returns the first `max_chars` chars of `s`, or all of it if it's shorter.
+pub const fn truncate_chars(s: &str, max_chars: usize) -> &str {
+    byte_range(s, 0, char_offset(s, max_chars))
+}
+
+/// Returns what [`const_truncate!`] keeps of `s` in front of `ellipsis`, counting bytes or, if
+/// `chars` is set, chars: all of `s` if it fits in `max`, or otherwise the longest prefix that
+/// leaves room for the ellipsis.
+#[doc(hidden)]
+pub const fn truncate_before_ellipsis<'a>(
+    s: &'a str,
+    max: usize,
+    ellipsis: &str,
+    chars: bool,
+) -> &'a str {
+    let (len, ellipsis_len) =
+        if chars { (char_count(s), char_count(ellipsis)) } else { (s.len(), ellipsis.len()) };
+    if len <= max {
+        return s;
+    }
+    if ellipsis_len > max {
+        panic!("const_truncate: ellipsis is longer than the limit");
+    }
+
+    if chars {
+        truncate_chars(s, max - ellipsis_len)
+    } else {
+        truncate(s, max - ellipsis_len)
+    }
+}
+
+/// Returns `s`, or panics with its length if it's longer than `max` bytes, or chars if `chars`
+/// is set.
+#[doc(hidden)]
+pub const fn check_len(s: &str, max: usize, chars: bool) -> &str {
+    let (len, unit) = if chars { (char_count(s), " chars") } else { (s.len(), " bytes") };
+    if len > max {
+        let mut msg = crate::ConstStr::<128>::new();
+        msg.push_str("const_truncate: string is ");
+        msg.push_str(crate::fmt::Arg(len).to_str().as_str());
+        msg.push_str(unit);
+        msg.push_str(" long, more than the limit of ");
+        msg.push_str(crate::fmt::Arg(max).to_str().as_str());
+        panic!("{}", msg.as_str())
+    }
+
+    s
+}
+
+/// Returns the number of chars in `s`.
+pub const fn char_count(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !is_continuation(bytes[i]) {
+            count += 1;
+        }
+        i += 1;
+    }
+
+    count
+}
+
+/// Returns `true` if `index` is the start or end of a char in `s`, like `str::is_char_boundary`.
+pub const fn is_char_boundary(s: &str, index: usize) -> bool {
+    if index == s.len() {
+        true
+    } else if index > s.len() {
+        false
+    } else {
+        !is_continuation(s.as_bytes()[index])
+    }
+}
+
+/// Returns the byte offset of the `n`th char of `s`, or `s.len()` if it has `n` chars.
+const fn char_offset(s: &str, n: usize) -> usize {
+    let bytes = s.as_bytes();
+    let mut seen = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !is_continuation(bytes[i]) {
+            if seen == n {
+                return i;
+            }
+            seen += 1;
+        }
+        i += 1;
+    }
+
+    bytes.len()
+}
+
+/// Wrapper used by [`const_substr!`] to accept any kind of `usize` range.
+///
+/// Works like [`ByteArg`](crate::bytes::ByteArg). `resolve` returns the start and end given the
+/// length of what's being sliced.
+#[doc(hidden)]
+pub struct Bounds<R>(pub R);
+
+impl Bounds<Range<usize>> {
+    pub const fn resolve(&self, _len: usize) -> (usize, usize) {
+        (self.0.start, self.0.end)
+    }
+}
+
+impl Bounds<RangeInclusive<usize>> {
+    pub const fn resolve(&self, _len: usize) -> (usize, usize) {
+        (*self.0.start(), *self.0.end() + 1)
+    }
+}
+
+impl Bounds<RangeFrom<usize>> {
+    pub const fn resolve(&self, len: usize) -> (usize, usize) {
+        (self.0.start, len)
+    }
+}
+
+impl Bounds<RangeTo<usize>> {
+    pub const fn resolve(&self, _len: usize) -> (usize, usize) {
+        (0, self.0.end)
+    }
+}
+
+impl Bounds<RangeToInclusive<usize>> {
+    pub const fn resolve(&self, _len: usize) -> (usize, usize) {
+        (0, self.0.end + 1)
+    }
+}
+
+impl Bounds<RangeFull> {
+    pub const fn resolve(&self, len: usize) -> (usize, usize) {
+        (0, len)
+    }
+}
+
+/// Slices a constant string by byte range, or by char range with `chars = `, into a
+/// `&'static str`.
+///
+/// `const_substr!(S, 2..5)` is `&S[2..5]`, and fails to compile if either end isn't on a char
+/// boundary. `const_substr!(S, chars = 2..5)` is the third to fifth chars of `S`.
+#[macro_export]
+macro_rules! const_substr {
+    ($s:expr, chars = $range:expr) => {{
+        const __S: &str = $s;
+        const __BOUNDS: (usize, usize) =
+            $crate::substr::Bounds($range).resolve($crate::substr::char_count(__S));
+        const __OUT: &str = $crate::substr::char_range(__S, __BOUNDS.0, __BOUNDS.1);
+        __OUT
+    }};
+    ($s:expr, $range:expr) => {{
+        const __S: &str = $s;
+        const __BOUNDS: (usize, usize) = $crate::substr::Bounds($range).resolve(__S.len());
+        const __OUT: &str = $crate::substr::byte_range(__S, __BOUNDS.0, __BOUNDS.1);
+        __OUT
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_truncate {
+    ($s:expr, $max:expr, $ellipsis:expr, $chars:expr) => {{
+        const __S: &str = $s;
+        const __ELLIPSIS: &str = $ellipsis;
+        const __HEAD: &str =
+            $crate::substr::truncate_before_ellipsis(__S, $max, __ELLIPSIS, $chars);
+        const __OUT: &str = if __HEAD.len() == __S.len() {
+            __S
+        } else {
+            $crate::const_concat!(__HEAD, __ELLIPSIS)
+        };
+        __OUT
+    }};
+}
+
+/// Truncates a constant string to a maximum length, into a `&'static str`, without splitting a
+/// char.
+///
+/// `const_truncate!(NAME, 63)` keeps as many whole chars of `NAME` as fit in 63 bytes, and
+/// `const_truncate!(NAME, chars = 20)` keeps at most 20 chars. With an ellipsis, the limit is in
+/// chars: `const_truncate!(NAME, 20, ellipsis = "…")` keeps `NAME` if it's at most 20 chars, and
+/// otherwise ends it with the ellipsis, which counts towards the limit. Use
+/// `const_truncate!(NAME, bytes = 63, ellipsis = "…")` for a limit in bytes instead.
+///
+/// With a leading `strict;`, as in `const_truncate!(strict; NAME, 63)`, the string is never
+/// changed, and compilation fails instead if it's longer than the limit.
+#[macro_export]
+macro_rules! const_truncate {
+    (strict; $s:expr, chars = $max:expr) => {{
+        const __OUT: &str = $crate::substr::check_len($s, $max, true);
+        __OUT
+    }};
+    (strict; $s:expr, $max:expr) => {{
+        const __OUT: &str = $crate::substr::check_len($s, $max, false);
+        __OUT
+    }};
+    ($s:expr, chars = $max:expr, ellipsis = $ellipsis:expr) => {
+        $crate::__const_truncate!($s, $max, $ellipsis, true)
+    };
+    ($s:expr, chars = $max:expr) => {{
+        const __OUT: &str = $crate::substr::truncate_chars($s, $max);
+        __OUT
+    }};
+    ($s:expr, bytes = $max:expr, ellipsis = $ellipsis:expr) => {
+        $crate::__const_truncate!($s, $max, $ellipsis, false)
+    };
+    ($s:expr, $max:expr, ellipsis = $ellipsis:expr) => {
+        $crate::__const_truncate!($s, $max, $ellipsis, true)
+    };
+    ($s:expr, $max:expr) => {{
+        const __OUT: &str = $crate::substr::truncate($s, $max);
+        __OUT
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{byte_range, char_range, check_len, truncate_before_ellipsis};
+    use crate::const_concat;
+
+    #[test]
+    fn byte_ranges() {
+        const URL: &str = const_concat!("https://", "example.com", "/päth");
+        const SCHEME: &str = const_substr!(URL, ..5);
+        const HOST: &str = const_substr!(URL, 8..19);
+        const PATH: &str = const_substr!(URL, 19..);
+
+        assert_eq!(SCHEME, "https");
+        assert_eq!(HOST, "example.com");
+        assert_eq!(PATH, "/päth");
+        assert_eq!(const_substr!(URL, ..), URL);
+        assert_eq!(const_substr!(PATH, 1..=3), "pä");
+    }
+
+    #[test]
+    fn char_ranges() {
+        const WORD: &str = "naïve café";
+
+        assert_eq!(const_substr!(WORD, chars = 2..5), "ïve");
+        assert_eq!(const_substr!(WORD, chars = 6..), "café");
+        assert_eq!(const_substr!(WORD, chars = ..=2), "naï");
+        assert_eq!(char_range(WORD, 10, 10), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "range end is not on a char boundary")]
+    fn byte_range_inside_char() {
+        byte_range("naïve", 0, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "range end is out of bounds")]
+    fn char_range_out_of_bounds() {
+        char_range("naïve", 0, 6);
+    }
+
+    #[test]
+    fn truncation() {
+        const NAME: &str = const_concat!("service-", "naïve-café");
+
+        assert_eq!(const_truncate!(NAME, 10), "service-na");
+        assert_eq!(const_truncate!(NAME, 11), "service-na");
+        assert_eq!(const_truncate!(NAME, 12), "service-naï");
+        assert_eq!(const_truncate!(NAME, 100), NAME);
+        assert_eq!(const_truncate!(NAME, chars = 11), "service-naï");
+        assert_eq!(const_truncate!(NAME, chars = 0), "");
+        assert_eq!(const_truncate!(NAME, bytes = 13, ellipsis = "…"), "service-na…");
+        assert_eq!(const_truncate!(NAME, 12, ellipsis = "…"), "service-naï…");
+        assert_eq!(const_truncate!(NAME, chars = 12, ellipsis = "…"), "service-naï…");
+        assert_eq!(const_truncate!(NAME, chars = 18, ellipsis = "…"), NAME);
+        assert_eq!(const_truncate!(strict; NAME, 20), NAME);
+        assert_eq!(const_truncate!(strict; NAME, chars = 18), NAME);
+        assert_eq!(truncate_before_ellipsis("abcd", 3, "...", false), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "const_truncate: string is 19 chars long, more than the limit of 18")]
+    fn too_long() {
+        check_len("service-naïve-cafés", 18, true);
+    }
+}
@@ -0,0 +1,105 @@
+//! UTF-16 encoding in constants.
+
+use crate::utf8::decode;
+
+/// Returns the number of UTF-16 code units needed to encode `s`.
+pub const fn encoded_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut len = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let (c, next) = decode(bytes, i);
+        len += c.len_utf16();
+        i = next;
+    }
+
+    len
+}
+
+/// Encodes `s` as UTF-16 into a `[u16; N]`.
+///
+/// Panics if `N` isn't [`encoded_len`] of `s`.
+pub const fn encode<const N: usize>(s: &str) -> [u16; N] {
+    if encoded_len(s) != N {
+        panic!("const_concat_utf16: output length `N` is not the encoded length");
+    }
+
+    let bytes = s.as_bytes();
+    let mut out = [0u16; N];
+    let mut i = 0;
+    let mut o = 0;
+    while i < bytes.len() {
+        let (c, next) = decode(bytes, i);
+        let c = c as u32;
+        if c >= 0x10000 {
+            let c = c - 0x10000;
+            out[o] = 0xd800 | (c >> 10) as u16;
+            out[o + 1] = 0xdc00 | (c & 0x3ff) as u16;
+            o += 2;
+        } else {
+            out[o] = c as u16;
+            o += 1;
+        }
+        i = next;
+    }
+
+    out
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __const_utf16 {
+    ($arg:expr) => {{
+        const __S: &str = $crate::fmt::Arg($arg).to_str().as_str();
+        const __OUT: [u16; $crate::utf16::encoded_len(__S)] =
+            $crate::utf16::encode::<{ $crate::utf16::encoded_len(__S) }>(__S);
+        __OUT
+    }};
+}
+
+/// Concatenates constants and encodes them as UTF-16, into a `&'static [u16; N]`.
+///
+/// Takes the same arguments as [`const_concat!`].
+#[macro_export]
+macro_rules! const_concat_utf16 {
+    ($($arg:expr),*) => {{
+        const __UNITS: &[u16] =
+            &$crate::const_concat_slices!(u16; $($crate::__const_utf16!($arg)),*);
+        // Copied into a constant of a known size, so that the result can be borrowed for
+        // `'static` wherever the macro is used.
+        const __OUT: [u16; __UNITS.len()] =
+            $crate::concat_slices::<u16, { __UNITS.len() }, 0, { __UNITS.len() }>(__UNITS, &[]);
+        &__OUT
+    }};
+    ($($arg:expr),*,) => {
+        $crate::const_concat_utf16!($($arg),*)
+    };
+}
+
+/// Like [`const_concat_utf16!`], but with a NUL terminator.
+#[macro_export]
+macro_rules! const_concat_utf16_nul {
+    ($($arg:expr),*) => {
+        $crate::const_concat_utf16!($($arg,)* '\0')
+    };
+    ($($arg:expr),*,) => {
+        $crate::const_concat_utf16_nul!($($arg),*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn utf16() {
+        const NAME: &str = "Clef 𝄞";
+        const VERSION: u8 = 2;
+        const WIDE: &[u16] = const_concat_utf16!(NAME, " v", VERSION);
+        const WIDE_NUL: &[u16] = const_concat_utf16_nul!(NAME, " é",);
+
+        assert_eq!(WIDE, "Clef 𝄞 v2".encode_utf16().collect::<Vec<_>>());
+        assert_eq!(WIDE_NUL, "Clef 𝄞 é\0".encode_utf16().collect::<Vec<_>>());
+        assert_eq!(&WIDE[5..7], [0xd834, 0xdd1e]);
+        assert_eq!(const_concat_utf16!(), &[]);
+        assert_eq!(const_concat_utf16_nul!(), &[0]);
+    }
+}
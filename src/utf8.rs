@@ -0,0 +1,33 @@
+//! Decoding UTF-8 in `const fn`s, which can't use `str::chars`.
+
+/// Decodes the char starting at `bytes[i]`, returning it and the index of the next one.
+///
+/// `bytes` must be valid UTF-8 and `i` must be on a char boundary.
+pub(crate) const fn decode(bytes: &[u8], i: usize) -> (char, usize) {
+    let b = bytes[i] as u32;
+    let (mut c, len) = if b < 0x80 {
+        (b, 1)
+    } else if b < 0xe0 {
+        (b & 0x1f, 2)
+    } else if b < 0xf0 {
+        (b & 0x0f, 3)
+    } else {
+        (b & 0x07, 4)
+    };
+
+    let mut j = 1;
+    while j < len {
+        c = (c << 6) | (bytes[i + j] & 0x3f) as u32;
+        j += 1;
+    }
+
+    match char::from_u32(c) {
+        Some(c) => (c, i + len),
+        None => unreachable!(),
+    }
+}
+
+/// Returns `true` if `b` continues a char rather than starting one.
+pub(crate) const fn is_continuation(b: u8) -> bool {
+    b & 0xc0 == 0x80
+}